@@ -2,7 +2,7 @@ use cosmwasm_std::{
     entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
 };
 use cw2::{get_contract_version, set_contract_version};
-use internnft::nft::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use internnft::nft::{ExecuteMsg, InstantiateMsg, InternExtension, MigrateMsg, QueryMsg};
 
 use crate::error::ContractError;
 use crate::execute as ExecHandler;
@@ -31,17 +31,85 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Mint {} => ExecHandler::execute_mint(deps, env, info),
+        ExecuteMsg::Mint { quantity, proof } => {
+            ExecHandler::execute_mint(deps, env, info, quantity, proof)
+        }
+        ExecuteMsg::MintWithVoucher { voucher, signature } => {
+            ExecHandler::execute_mint_with_voucher(deps, env, info, voucher, signature)
+        }
+        ExecuteMsg::Airdrop { recipients } => {
+            ExecHandler::execute_airdrop(deps, env, info, recipients)
+        }
+        ExecuteMsg::RollTraits { token_ids } => {
+            ExecHandler::execute_roll_traits(deps, env, info, token_ids)
+        }
+        ExecuteMsg::Reveal {} => ExecHandler::execute_reveal(deps, env, info),
+        ExecuteMsg::FinalizeReveal {} => ExecHandler::execute_finalize_reveal(deps),
+        ExecuteMsg::UpdateTokenRoyalty { token_id, royalty } => {
+            ExecHandler::execute_update_token_royalty(deps, info, token_id, royalty)
+        }
+        ExecuteMsg::UpdateTokenImage { token_id, image } => {
+            ExecHandler::execute_update_token_image(deps, info, token_id, image)
+        }
+        ExecuteMsg::WithdrawGold { token_id, amount } => {
+            ExecHandler::execute_withdraw_gold(deps, env, info, token_id, amount)
+        }
+        ExecuteMsg::Receive(msg) => ExecHandler::execute_receive(deps, env, info, msg),
+        ExecuteMsg::Burn { token_id } => ExecHandler::execute_burn(deps, env, info, token_id),
         ExecuteMsg::UpdateConfig { config } => {
             ExecHandler::execute_update_config(deps, info, config)
         }
+        ExecuteMsg::SetProfile {
+            token_id,
+            name,
+            description,
+        } => ExecHandler::execute_set_profile(deps, env, info, token_id, name, description),
         ExecuteMsg::Withdraw { amount } => ExecHandler::execute_withdraw(deps, env, info, amount),
+        ExecuteMsg::ProposeNewOwner { new_owner, expires } => {
+            ExecHandler::execute_propose_new_owner(deps, env, info, new_owner, expires)
+        }
+        ExecuteMsg::SetPaused {
+            minting,
+            transfers,
+            trait_updates,
+        } => ExecHandler::execute_set_paused(deps, info, minting, transfers, trait_updates),
+        ExecuteMsg::RecountLevels { limit } => {
+            ExecHandler::execute_recount_levels(deps, info, limit)
+        }
+        ExecuteMsg::AcceptOwnership {} => ExecHandler::execute_accept_ownership(deps, env, info),
+        ExecuteMsg::RenounceOwnership {} => ExecHandler::execute_renounce_ownership(deps, info),
         ExecuteMsg::UpdateTrait {
             token_id,
             exp,
             gold,
             stamina,
-        } => ExecHandler::execute_update_traits(deps, env, info, token_id, exp, gold, stamina),
+            reason,
+        } => {
+            let extension = InternExtension {
+                experience: exp,
+                gold,
+                stamina,
+            };
+            ExecHandler::execute_update_traits(deps, env, info, token_id, extension, reason)
+        }
+        ExecuteMsg::AdjustTraits {
+            token_id,
+            deltas,
+            expected,
+            reason,
+        } => {
+            ExecHandler::execute_adjust_traits(deps, env, info, token_id, deltas, expected, reason)
+        }
+        ExecuteMsg::AddUpdater { address, traits } => {
+            ExecHandler::execute_add_updater(deps, info, address, traits)
+        }
+        ExecuteMsg::RemoveUpdater { address } => {
+            ExecHandler::execute_remove_updater(deps, info, address)
+        }
+        ExecuteMsg::UpdatePayees { payees } => {
+            ExecHandler::execute_update_payees(deps, env, info, payees)
+        }
+        ExecuteMsg::Distribute {} => ExecHandler::execute_distribute(deps, env),
         _ => ExecHandler::cw721_base_execute(deps, env, info, msg),
     }
 }
@@ -50,6 +118,8 @@ pub fn execute(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&QueryHandler::query_config(deps)?),
+        QueryMsg::Owner {} => to_binary(&QueryHandler::query_owner(deps)?),
+        QueryMsg::PauseState {} => to_binary(&QueryHandler::query_pause_state(deps)?),
         QueryMsg::InternNftInfo { token_id } => {
             to_binary(&QueryHandler::query_intern_nft_info(deps, token_id)?)
         }
@@ -69,6 +139,50 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::NumTokensForOwner { owner } => {
             to_binary(&QueryHandler::query_num_tokens_for_owner(deps, owner)?)
         }
+        QueryMsg::AllowlistMints { address } => {
+            to_binary(&QueryHandler::query_allowlist_mints(deps, address)?)
+        }
+        QueryMsg::Provenance {} => to_binary(&QueryHandler::query_provenance(deps)?),
+        QueryMsg::RoyaltyInfo {
+            token_id,
+            sale_price,
+        } => to_binary(&QueryHandler::query_royalty_info(
+            deps, token_id, sale_price,
+        )?),
+        QueryMsg::CheckRoyalties {} => to_binary(&QueryHandler::query_check_royalties(deps)?),
+        QueryMsg::BurnedTokens { start_after, limit } => to_binary(
+            &QueryHandler::query_burned_tokens(deps, start_after, limit)?,
+        ),
+        QueryMsg::MintStatus { address } => {
+            to_binary(&QueryHandler::query_mint_status(deps, env, address)?)
+        }
+        QueryMsg::TraitHistory {
+            token_id,
+            start_after,
+            limit,
+        } => to_binary(&QueryHandler::query_trait_history(
+            deps,
+            token_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Leaderboard {
+            intern_trait,
+            start_after,
+            limit,
+            order,
+        } => to_binary(&QueryHandler::query_leaderboard(
+            deps,
+            intern_trait,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::CollectionStats {} => to_binary(&QueryHandler::query_collection_stats(deps)?),
+        QueryMsg::Payees {} => to_binary(&QueryHandler::query_payees(deps, env)?),
+        QueryMsg::Updaters { start_after, limit } => {
+            to_binary(&QueryHandler::query_updaters(deps, start_after, limit)?)
+        }
         _ => QueryHandler::cw721_base_query(deps, env, msg),
     }
 }
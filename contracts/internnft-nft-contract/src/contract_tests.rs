@@ -1,24 +1,62 @@
 #![cfg(test)]
 use std::str;
 
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{BankMsg, Binary, Coin, DepsMut};
-use internnft::nft::{Config, InstantiateMsg};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    attr, from_binary, from_slice, to_binary, to_vec, Addr, BankMsg, Binary, Coin, CosmosMsg,
+    Decimal, DepsMut, StdError, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::Expiration;
+use internnft::nft::{
+    CollectionStatsResponse, Config, ConfigUpdate, Cw721Metadata, ExecuteMsg, GoldHookMsg,
+    GoldToken, InstantiateMsg, InternExtension, InternTokenInfo, InternTrait, LevelCount,
+    LevelCurve, MintPhase, MintVoucher, OwnerResponse, PauseState, Payee, PayeeInfo, PendingOwner,
+    PlaceholderMetadata, RarityTier, Royalty, SortOrder, TraitChange, TraitDeltas, TraitUpdater,
+};
+use internnft::staking::{Cw721HookMsg, ExecuteMsg as StakingExecuteMsg};
+use rsa::pkcs1::FromRsaPrivateKey;
+use rsa::{Hash, PaddingScheme, RsaPrivateKey};
+use sha2::{Digest, Sha256};
 
 use crate::contract::instantiate;
 use crate::error::ContractError;
 use crate::execute as ExecHandler;
+use crate::mock_querier::{mock_dependencies, TERRAND};
 use crate::query as QueryHandler;
+use crate::state::tokens;
 
 const OWNER: &str = "owner";
 const NONOWNER: &str = "nonowner";
 
+// PKCS#1 DER encoded RSA key pair used to sign test vouchers
+const VOUCHER_PRIVATE_KEY: &str = "MIICXgIBAAKBgQDY+S7AeB9pnJNai093PgUxh86VuJox7bEf1tICdbMgkT6FA1Av8+zGASC0v3GETJqo+otXAtb2yzCE/TIEMO1AZ0IcB5oTKI70I6PjwRlKsMrLYAu6xlcSJK36Su0og7DcL7eY/MtyXQU7fPpTGJZZcKoaQknMM/9hPKLY+FWAuwIDAQABAoGAKQrx0gym/IZWxWDm1kqQFu92n5G88UbiI4CyVy7GhJmU9vpKd9evaUEbW7V/FqSa+O3z37C00xhahD2U8YAD0Lquyc49uihVDGnVFUNFmrEkHVScONmqNCrFviFu5mjrZHSO+e2H0rWw7aAGNrU+WPJI2Bwjv0Wj1vEFhCZ3zKkCQQD4QSyF0MP3xKnNA2EuGKp59CBGrAMP4LH0sfgZ4/g4nrSdaZeLCBbWtrmdLXRdz1aEgGPCscFnHftOKQLGKufNAkEA374p3RxrAU+szQEMLfbGxfgCNQigXBigoxcag4hbBat5aLoerK7jZF2DcdIkL/H7AB2Fac+ab0/ydW+XNptypwJBAKvEZGreLIllYFtCaF39oQ6qdB6R67hzro1xCgnX6yo53RxBMCJ5YVwEp97x0V8LE4CwWNYgKYIQg43r4Eh+7U0CQQC3iGcQ0GHaRoAp/eT+w0JUXFd7J2NbiHwkrCv8n1qC8SD7TkkhP6iZyLetI0K57ZYGhzDMFhbJnlv2Bx6UFblbAkEAn3ZK0g7P8VYobWUj2JciUB2MZ3f18koyaRR2UF8ueAimhEA1XKn/Xae62QHjIbB4OROtW0t15W1YZZSxHisTXA==";
+const VOUCHER_PUBLIC_KEY: &str = "MIGJAoGBANj5LsB4H2mck1qLT3c+BTGHzpW4mjHtsR/W0gJ1syCRPoUDUC/z7MYBILS/cYRMmqj6i1cC1vbLMIT9MgQw7UBnQhwHmhMojvQjo+PBGUqwystgC7rGVxIkrfpK7SiDsNwvt5j8y3JdBTt8+lMYlllwqhpCScwz/2E8otj4VYC7AgMBAAE=";
+
 fn mock_config() -> Config {
     Config {
         mint_fee: Coin::new(0, "uluna"),
         token_supply: 10000,
         wallet_limit: 5,
         staking_contract: "staking_contract".to_string(),
+        voucher_public_key: None,
+        allowlist_root: None,
+        allowlist_limit: 2,
+        mint_phases: vec![],
+        reserved_supply: 0,
+        terrand_addr: None,
+        rarity_tiers: vec![],
+        placeholder: None,
+        burn_redemption: None,
+        royalty: None,
+        base_uri: None,
+        image_base_uri: None,
+        render_svg: false,
+        level_curve: LevelCurve::default(),
+        gold_token: None,
+        transfer_wallet_limit: false,
+        wallet_limit_exempt: vec![],
+        profile_fee: 0,
     }
 }
 
@@ -30,6 +68,7 @@ fn setup_contract(
 ) {
     let mut msg = InstantiateMsg {
         config: mock_config(),
+        provenance_hash: None,
     };
     if let Some(mint_fee) = mint_fee {
         msg.config.mint_fee = mint_fee;
@@ -45,6 +84,66 @@ fn setup_contract(
     assert_eq!(0, res.messages.len());
 }
 
+fn phase(name: &str, start_time: u64, end_time: Option<u64>) -> MintPhase {
+    MintPhase {
+        name: name.to_string(),
+        start_time,
+        end_time,
+        price: Coin::new(0, "uluna"),
+        wallet_limit: 5,
+        supply: None,
+        allowlist: false,
+    }
+}
+
+fn tier(name: &str, weight: u32, min_stamina: u64, max_stamina: u64) -> RarityTier {
+    RarityTier {
+        name: name.to_string(),
+        weight,
+        min_stamina,
+        max_stamina,
+    }
+}
+
+fn sign_voucher(voucher: &MintVoucher) -> Binary {
+    let key_der = Binary::from_base64(VOUCHER_PRIVATE_KEY).unwrap();
+    let key = RsaPrivateKey::from_pkcs1_der(key_der.as_slice()).unwrap();
+    let digest = Sha256::digest(&to_vec(voucher).unwrap());
+    let signature = key
+        .sign(
+            PaddingScheme::new_pkcs1v15_sign(Some(Hash::SHA2_256)),
+            &digest,
+        )
+        .unwrap();
+    Binary::from(signature)
+}
+
+fn merkle_hash(first: &[u8], second: &[u8]) -> Vec<u8> {
+    let (first, second) = if first <= second {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    Sha256::new().chain(first).chain(second).finalize().to_vec()
+}
+
+// builds the allowlist tree ((a, b), c) and returns the root with a proof for a
+fn allowlist_tree(addresses: [&str; 3]) -> (Binary, Vec<Binary>) {
+    let leaves: Vec<Vec<u8>> = addresses
+        .iter()
+        .map(|address| Sha256::digest(address.as_bytes()).to_vec())
+        .collect();
+    let branch = merkle_hash(&leaves[0], &leaves[1]);
+    let root = merkle_hash(&branch, &leaves[2]);
+    (
+        Binary::from(root),
+        vec![
+            Binary::from(leaves[1].clone()),
+            Binary::from(leaves[2].clone()),
+        ],
+    )
+}
+
 #[allow(dead_code)]
 fn as_json(binary: &Binary) -> serde_json::Value {
     let b64_binary = binary.to_base64();
@@ -70,33 +169,151 @@ fn update_and_query_config() {
     assert_eq!(res, initial_config);
 
     // change the config
+    let update = ConfigUpdate {
+        mint_fee: Some(Coin::new(10000, "uluna")),
+        base_uri: Some(Some("ipfs://metadata".to_string())),
+        ..ConfigUpdate::default()
+    };
     let mut new_config = initial_config.clone();
     new_config.mint_fee = Coin::new(10000, "uluna");
+    new_config.base_uri = Some("ipfs://metadata".to_string());
 
     // nonowner can't update config
-    let err = ExecHandler::execute_update_config(
-        deps.as_mut(),
-        mock_info(NONOWNER, &[]),
-        new_config.clone(),
-    )
-    .unwrap_err();
+    let err =
+        ExecHandler::execute_update_config(deps.as_mut(), mock_info(NONOWNER, &[]), update.clone())
+            .unwrap_err();
     assert_eq!(err, ContractError::Unauthorized {});
 
     // check config was unchanged
     let res = QueryHandler::query_config(deps.as_ref()).unwrap();
     assert_eq!(res, initial_config);
 
-    // owner can update config
-    let _ = ExecHandler::execute_update_config(
-        deps.as_mut(),
-        mock_info(OWNER, &[]),
-        new_config.clone(),
-    )
-    .unwrap();
+    // owner can update config, leaving the other fields as they are
+    let res =
+        ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_config"),
+            attr("old_mint_fee", r#"{"denom":"uluna","amount":"0"}"#),
+            attr("new_mint_fee", r#"{"denom":"uluna","amount":"10000"}"#),
+            attr("old_base_uri", "null"),
+            attr("new_base_uri", r#""ipfs://metadata""#),
+        ]
+    );
 
     // check config was updated
     let res = QueryHandler::query_config(deps.as_ref()).unwrap();
     assert_eq!(res, new_config);
+
+    // fields set to null are cleared, unchanged fields aren't reported
+    let update: ConfigUpdate =
+        from_slice(br#"{"base_uri":null,"mint_fee":{"denom":"uluna","amount":"10000"}}"#).unwrap();
+    let res =
+        ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            attr("action", "update_config"),
+            attr("old_base_uri", r#""ipfs://metadata""#),
+            attr("new_base_uri", "null"),
+        ]
+    );
+    let res = QueryHandler::query_config(deps.as_ref()).unwrap();
+    assert_eq!(res.base_uri, None);
+
+    // updates are validated
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        Some(3),
+        None,
+    )
+    .unwrap();
+    let invalid = [
+        (
+            ConfigUpdate {
+                token_supply: Some(2),
+                ..ConfigUpdate::default()
+            },
+            "token supply cannot be less than the number of interns minted",
+        ),
+        (
+            ConfigUpdate {
+                mint_fee: Some(Coin::new(100, "")),
+                ..ConfigUpdate::default()
+            },
+            "mint fee denom cannot be empty",
+        ),
+        (
+            ConfigUpdate {
+                staking_contract: Some("x".to_string()),
+                ..ConfigUpdate::default()
+            },
+            "Invalid input: human address too short",
+        ),
+        (
+            ConfigUpdate {
+                reserved_supply: Some(10001),
+                ..ConfigUpdate::default()
+            },
+            "reserved supply cannot exceed the token supply",
+        ),
+        (
+            ConfigUpdate {
+                mint_phases: Some(vec![phase("", 100, None)]),
+                ..ConfigUpdate::default()
+            },
+            "mint phase name cannot be empty",
+        ),
+        (
+            ConfigUpdate {
+                mint_phases: Some(vec![phase("public", 100, None), phase("public", 300, None)]),
+                ..ConfigUpdate::default()
+            },
+            "mint phase names must be unique",
+        ),
+        (
+            ConfigUpdate {
+                mint_phases: Some(vec![phase("public", 100, Some(100))]),
+                ..ConfigUpdate::default()
+            },
+            "mint phases must end after they start",
+        ),
+        (
+            ConfigUpdate {
+                rarity_tiers: Some(vec![tier("rare", 1, 80, 60)]),
+                ..ConfigUpdate::default()
+            },
+            "rarity tier minimum stamina cannot exceed its maximum",
+        ),
+        (
+            ConfigUpdate {
+                rarity_tiers: Some(vec![tier("common", 0, 80, 100), tier("rare", 0, 120, 150)]),
+                ..ConfigUpdate::default()
+            },
+            "at least one rarity tier needs a positive weight",
+        ),
+    ];
+    for (update, msg) in &invalid {
+        let err = ExecHandler::execute_update_config(
+            deps.as_mut(),
+            mock_info(OWNER, &[]),
+            update.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Std(StdError::generic_err(*msg)));
+    }
+    ExecHandler::execute_update_config(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        ConfigUpdate {
+            token_supply: Some(3),
+            ..ConfigUpdate::default()
+        },
+    )
+    .unwrap();
 }
 
 #[test]
@@ -132,3 +349,2784 @@ fn withdraw() {
         .into()
     )
 }
+
+#[test]
+fn ownership_transfer() {
+    let balance = vec![Coin::new(10000, "uluna")];
+    let mut deps = mock_dependencies(&balance);
+    setup_contract(deps.as_mut(), None, None, None);
+    let withdraw = |deps: DepsMut, sender: &str| {
+        ExecHandler::execute_withdraw(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            vec![Coin::new(100, "uluna")],
+        )
+    };
+
+    // only the owner can propose
+    let err = ExecHandler::execute_propose_new_owner(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        NONOWNER.to_string(),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = ExecHandler::execute_accept_ownership(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingOwner {});
+
+    // proposals expire after a week by default
+    let mut env = mock_env();
+    ExecHandler::execute_propose_new_owner(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        "multisig".to_string(),
+        None,
+    )
+    .unwrap();
+    let res = QueryHandler::query_owner(deps.as_ref()).unwrap();
+    assert_eq!(
+        res,
+        OwnerResponse {
+            owner: Some(OWNER.to_string()),
+            pending_owner: Some(PendingOwner {
+                address: "multisig".to_string(),
+                expires: Expiration::AtTime(env.block.time.plus_seconds(7 * 24 * 60 * 60)),
+            }),
+        }
+    );
+    env.block.time = env.block.time.plus_seconds(7 * 24 * 60 * 60);
+    let err = ExecHandler::execute_accept_ownership(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("multisig", &[]),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::OwnershipProposalExpired {});
+
+    // proposals can't already be expired
+    let err = ExecHandler::execute_propose_new_owner(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        "multisig".to_string(),
+        Some(Expiration::AtHeight(env.block.height)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::OwnershipProposalAlreadyExpired {});
+
+    // only the proposed owner can accept
+    ExecHandler::execute_propose_new_owner(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        "multisig".to_string(),
+        Some(Expiration::AtHeight(env.block.height + 100)),
+    )
+    .unwrap();
+    let err =
+        ExecHandler::execute_accept_ownership(deps.as_mut(), env.clone(), mock_info(NONOWNER, &[]))
+            .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    ExecHandler::execute_accept_ownership(deps.as_mut(), env, mock_info("multisig", &[])).unwrap();
+    let res = QueryHandler::query_owner(deps.as_ref()).unwrap();
+    assert_eq!(
+        res,
+        OwnerResponse {
+            owner: Some("multisig".to_string()),
+            pending_owner: None,
+        }
+    );
+
+    // the previous owner loses access
+    let err = withdraw(deps.as_mut(), OWNER).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    withdraw(deps.as_mut(), "multisig").unwrap();
+
+    // renouncing clears the owner and any pending proposal
+    ExecHandler::execute_propose_new_owner(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("multisig", &[]),
+        OWNER.to_string(),
+        None,
+    )
+    .unwrap();
+    let err =
+        ExecHandler::execute_renounce_ownership(deps.as_mut(), mock_info(OWNER, &[])).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    ExecHandler::execute_renounce_ownership(deps.as_mut(), mock_info("multisig", &[])).unwrap();
+    let res = QueryHandler::query_owner(deps.as_ref()).unwrap();
+    assert_eq!(
+        res,
+        OwnerResponse {
+            owner: None,
+            pending_owner: None,
+        }
+    );
+    let err = withdraw(deps.as_mut(), "multisig").unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err =
+        ExecHandler::execute_accept_ownership(deps.as_mut(), mock_env(), mock_info(OWNER, &[]))
+            .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingOwner {});
+}
+
+#[test]
+fn pause() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+    let mint = |deps: DepsMut| {
+        ExecHandler::execute_mint(deps, mock_env(), mock_info(OWNER, &[]), None, None)
+    };
+    let transfer = |deps: DepsMut, sender: &str, recipient: &str| {
+        ExecHandler::cw721_base_execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+    };
+    let adjust_traits = |deps: DepsMut| {
+        ExecHandler::execute_adjust_traits(
+            deps,
+            mock_env(),
+            mock_info("staking_contract", &[]),
+            "1".to_string(),
+            TraitDeltas {
+                gold: 10,
+                ..TraitDeltas::default()
+            },
+            None,
+            None,
+        )
+    };
+    let paused = |operation: &str| ContractError::Paused {
+        operation: operation.to_string(),
+    };
+
+    // only the owner can pause
+    let err = ExecHandler::execute_set_paused(
+        deps.as_mut(),
+        mock_info(NONOWNER, &[]),
+        Some(true),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = QueryHandler::query_pause_state(deps.as_ref()).unwrap();
+    assert_eq!(res, PauseState::default());
+
+    // each operation is paused independently
+    ExecHandler::execute_set_paused(deps.as_mut(), mock_info(OWNER, &[]), Some(true), None, None)
+        .unwrap();
+    assert_eq!(mint(deps.as_mut()).unwrap_err(), paused("minting"));
+    let status =
+        QueryHandler::query_mint_status(deps.as_ref(), mock_env(), Some(OWNER.to_string()))
+            .unwrap();
+    assert!(!status.open);
+    assert_eq!(status.remaining_allowance, Some(0));
+    transfer(deps.as_mut(), OWNER, NONOWNER).unwrap();
+    adjust_traits(deps.as_mut()).unwrap();
+
+    ExecHandler::execute_set_paused(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        None,
+        Some(true),
+        Some(true),
+    )
+    .unwrap();
+    let res = QueryHandler::query_pause_state(deps.as_ref()).unwrap();
+    assert_eq!(
+        res,
+        PauseState {
+            minting: true,
+            transfers: true,
+            trait_updates: true,
+        }
+    );
+    assert_eq!(
+        transfer(deps.as_mut(), NONOWNER, OWNER).unwrap_err(),
+        paused("transfers")
+    );
+    assert_eq!(
+        adjust_traits(deps.as_mut()).unwrap_err(),
+        paused("trait updates")
+    );
+
+    // approvals aren't transfers
+    ExecHandler::cw721_base_execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Approve {
+            spender: "addr2".to_string(),
+            token_id: "1".to_string(),
+            expires: None,
+        },
+    )
+    .unwrap();
+
+    // resuming leaves the other pauses in place
+    ExecHandler::execute_set_paused(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        Some(false),
+        Some(false),
+        None,
+    )
+    .unwrap();
+    mint(deps.as_mut()).unwrap();
+    transfer(deps.as_mut(), NONOWNER, OWNER).unwrap();
+    assert_eq!(
+        adjust_traits(deps.as_mut()).unwrap_err(),
+        paused("trait updates")
+    );
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.owner, Addr::unchecked(OWNER));
+    assert_eq!(token.extension.gold, 10);
+}
+
+#[test]
+fn mint() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(
+        deps.as_mut(),
+        Some(Coin::new(100, "uluna")),
+        Some(3),
+        Some(2),
+    );
+
+    // nonowner must pay the mint fee
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("insufficient funds sent"))
+    );
+
+    // mint the first intern
+    let res = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(100, "uluna")]),
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == "1"));
+
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.owner.to_string(), NONOWNER.to_string());
+    assert_eq!(token.name, "intern #1");
+    assert_eq!(
+        token.extension,
+        InternExtension {
+            experience: 0,
+            gold: 0,
+            stamina: 100,
+        }
+    );
+
+    // the owner mints for free
+    let res =
+        ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+            .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == "2"));
+}
+
+#[test]
+fn mint_limits() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, Some(3), Some(2));
+
+    for _ in 0..2 {
+        ExecHandler::execute_mint(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(NONOWNER, &[]),
+            None,
+            None,
+        )
+        .unwrap();
+    }
+
+    // wallet limit is enforced
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::WalletLimit {});
+
+    // token supply is enforced
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+    let err =
+        ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+            .unwrap_err();
+    assert_eq!(err, ContractError::SupplyExhausted {});
+
+    let res =
+        QueryHandler::query_num_tokens_for_owner(deps.as_ref(), NONOWNER.to_string()).unwrap();
+    assert_eq!(res.count, 2);
+}
+
+#[test]
+fn mint_with_voucher() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, Some(3));
+
+    let voucher = MintVoucher {
+        contract: MOCK_CONTRACT_ADDR.to_string(),
+        recipient: NONOWNER.to_string(),
+        quantity: 2,
+        price: Coin::new(50, "uluna"),
+        expires: mock_env().block.time.seconds() + 100,
+        nonce: 1,
+    };
+    let signature = sign_voucher(&voucher);
+    let funds = vec![Coin::new(100, "uluna")];
+
+    // vouchers are rejected until a public key is configured
+    let err = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &funds),
+        voucher.clone(),
+        signature.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VouchersDisabled {});
+
+    let update = ConfigUpdate {
+        wallet_limit: Some(3),
+        voucher_public_key: Some(Some(Binary::from_base64(VOUCHER_PUBLIC_KEY).unwrap())),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    // tampered vouchers fail verification
+    let mut tampered = voucher.clone();
+    tampered.quantity = 3;
+    let err = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &funds),
+        tampered,
+        signature.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidSignature {});
+
+    // vouchers only work at the contract they were issued for
+    let other_contract = MintVoucher {
+        contract: "other_contract".to_string(),
+        ..voucher.clone()
+    };
+    let err = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &funds),
+        other_contract.clone(),
+        sign_voucher(&other_contract),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VoucherWrongContract {});
+
+    // vouchers have to mint something
+    let empty = MintVoucher {
+        quantity: 0,
+        ..voucher.clone()
+    };
+    let err = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &funds),
+        empty.clone(),
+        sign_voucher(&empty),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidQuantity {});
+
+    // the full price must be paid
+    let err = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(50, "uluna")]),
+        voucher.clone(),
+        signature.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("insufficient funds sent"))
+    );
+
+    // redeem the voucher
+    let res = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &funds),
+        voucher.clone(),
+        signature.clone(),
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == "1,2"));
+    let res =
+        QueryHandler::query_num_tokens_for_owner(deps.as_ref(), NONOWNER.to_string()).unwrap();
+    assert_eq!(res.count, 2);
+
+    // vouchers can't be replayed
+    let err = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &funds),
+        voucher.clone(),
+        signature,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VoucherUsed {});
+
+    // expired vouchers are rejected
+    let expired = MintVoucher {
+        nonce: 2,
+        expires: mock_env().block.time.seconds() - 1,
+        ..voucher
+    };
+    let err = ExecHandler::execute_mint_with_voucher(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &funds),
+        expired.clone(),
+        sign_voucher(&expired),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::VoucherExpired {});
+}
+
+#[test]
+fn allowlist_mint() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, Some(1));
+
+    let (root, proof) = allowlist_tree([NONOWNER, "addr1", "addr2"]);
+
+    // allowlist mints are rejected until a root is configured
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        None,
+        Some(proof.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AllowlistDisabled {});
+
+    let update = ConfigUpdate {
+        wallet_limit: Some(1),
+        allowlist_root: Some(Some(root)),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    // addresses outside of the allowlist can't use the proof
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr3", &[]),
+        None,
+        Some(proof.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotAllowlisted {});
+
+    // allowlist mints don't count against the wallet limit
+    for _ in 0..2 {
+        ExecHandler::execute_mint(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(NONOWNER, &[]),
+            None,
+            Some(proof.clone()),
+        )
+        .unwrap();
+    }
+    let res = QueryHandler::query_allowlist_mints(deps.as_ref(), NONOWNER.to_string()).unwrap();
+    assert_eq!(res.minted, 2);
+    assert_eq!(res.remaining, 0);
+
+    // but the allowlist allotment is enforced
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        None,
+        Some(proof),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AllowlistLimit {});
+
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "2".to_string()).unwrap();
+    assert_eq!(token.owner, Addr::unchecked(NONOWNER));
+}
+
+#[test]
+fn mint_phases() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    let start = mock_env().block.time.seconds();
+    let (root, proof) = allowlist_tree([NONOWNER, "addr1", "addr2"]);
+    let update = ConfigUpdate {
+        allowlist_root: Some(Some(root)),
+        mint_phases: Some(vec![
+            MintPhase {
+                name: "allowlist".to_string(),
+                start_time: start + 100,
+                end_time: Some(start + 200),
+                price: Coin::new(50, "uluna"),
+                wallet_limit: 1,
+                supply: None,
+                allowlist: true,
+            },
+            MintPhase {
+                name: "public".to_string(),
+                start_time: start + 300,
+                end_time: None,
+                price: Coin::new(100, "uluna"),
+                wallet_limit: 3,
+                supply: Some(2),
+                allowlist: false,
+            },
+        ]),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    let mut env = mock_env();
+
+    // minting is closed before the first phase
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONOWNER, &[Coin::new(100, "uluna")]),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MintClosed {});
+    let status =
+        QueryHandler::query_mint_status(deps.as_ref(), env.clone(), Some(NONOWNER.to_string()))
+            .unwrap();
+    assert!(!status.open);
+    assert_eq!(status.remaining_allowance, Some(0));
+
+    // the allowlist phase requires a proof and charges the phase price
+    env.block.time = env.block.time.plus_seconds(150);
+    let funds = vec![Coin::new(50, "uluna")];
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("addr3", &funds),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NotAllowlisted {});
+
+    let status =
+        QueryHandler::query_mint_status(deps.as_ref(), env.clone(), Some(NONOWNER.to_string()))
+            .unwrap();
+    assert!(status.open);
+    assert_eq!(status.phase.unwrap().name, "allowlist");
+    assert_eq!(status.price, Coin::new(50, "uluna"));
+    assert_eq!(status.remaining_allowance, Some(1));
+
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONOWNER, &funds),
+        None,
+        Some(proof.clone()),
+    )
+    .unwrap();
+
+    // the phase wallet limit is enforced
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONOWNER, &funds),
+        None,
+        Some(proof),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::WalletLimit {});
+
+    // closed between phases
+    env.block.time = env.block.time.plus_seconds(100);
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONOWNER, &funds),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::MintClosed {});
+
+    // the public phase has its own price and supply
+    env.block.time = env.block.time.plus_seconds(100);
+    let funds = vec![Coin::new(100, "uluna")];
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONOWNER, &[Coin::new(50, "uluna")]),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("insufficient funds sent"))
+    );
+
+    for address in &[NONOWNER, "addr1"] {
+        ExecHandler::execute_mint(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(address, &funds),
+            None,
+            None,
+        )
+        .unwrap();
+    }
+    let status =
+        QueryHandler::query_mint_status(deps.as_ref(), env.clone(), Some(NONOWNER.to_string()))
+            .unwrap();
+    assert_eq!(status.remaining_supply, 0);
+    assert_eq!(status.remaining_allowance, Some(0));
+
+    let err = ExecHandler::execute_mint(deps.as_mut(), env, mock_info("addr2", &funds), None, None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::SupplyExhausted {});
+}
+
+#[test]
+fn batch_mint() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(
+        deps.as_mut(),
+        Some(Coin::new(100, "uluna")),
+        Some(5),
+        Some(3),
+    );
+
+    // the mint fee is charged for every intern in the batch
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(200, "uluna")]),
+        Some(3),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("insufficient funds sent"))
+    );
+
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        Some(0),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidQuantity {});
+
+    // the wallet limit applies to the whole batch
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(400, "uluna")]),
+        Some(4),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::WalletLimit {});
+
+    let res = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(300, "uluna")]),
+        Some(3),
+        None,
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == "1,2,3"));
+
+    // and so does the token supply
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        Some(3),
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SupplyExhausted {});
+
+    let res =
+        QueryHandler::query_num_tokens_for_owner(deps.as_ref(), NONOWNER.to_string()).unwrap();
+    assert_eq!(res.count, 3);
+}
+
+#[test]
+fn airdrop() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(
+        deps.as_mut(),
+        Some(Coin::new(100, "uluna")),
+        Some(5),
+        Some(1),
+    );
+
+    let update = ConfigUpdate {
+        mint_fee: Some(Coin::new(100, "uluna")),
+        token_supply: Some(5),
+        wallet_limit: Some(1),
+        reserved_supply: Some(3),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    let recipients = vec![(NONOWNER.to_string(), 2), ("addr1".to_string(), 1)];
+
+    // nonowner can't airdrop
+    let err = ExecHandler::execute_airdrop(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        recipients.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // public mints can't consume the reserved supply
+    let status = QueryHandler::query_mint_status(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(status.remaining_supply, 2);
+
+    // airdrops are free and ignore the wallet limit
+    let res =
+        ExecHandler::execute_airdrop(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), recipients)
+            .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == "1,2,3"));
+    let res =
+        QueryHandler::query_num_tokens_for_owner(deps.as_ref(), NONOWNER.to_string()).unwrap();
+    assert_eq!(res.count, 2);
+
+    // the reserve is used up, so the rest of the supply stays open to the public
+    let status = QueryHandler::query_mint_status(deps.as_ref(), mock_env(), None).unwrap();
+    assert_eq!(status.remaining_supply, 2);
+
+    // airdrops still count against the token supply
+    let err = ExecHandler::execute_airdrop(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        vec![("addr2".to_string(), 3)],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SupplyExhausted {});
+}
+
+#[test]
+fn mint_rolls_starting_traits() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    let update = ConfigUpdate {
+        terrand_addr: Some(Some(TERRAND.to_string())),
+        rarity_tiers: Some(vec![
+            RarityTier {
+                name: "common".to_string(),
+                weight: 90,
+                min_stamina: 50,
+                max_stamina: 100,
+            },
+            RarityTier {
+                name: "legendary".to_string(),
+                weight: 10,
+                min_stamina: 200,
+                max_stamina: 250,
+            },
+        ]),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    // interns commit to a terrand round that isn't published yet, and start without stamina
+    deps.querier.update_terrand_round(1);
+    let res = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        Some(3),
+        None,
+    )
+    .unwrap();
+    assert!(!res.attributes.iter().any(|attr| attr.key == "stamina"));
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "roll_round" && attr.value == "2"));
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.extension.stamina, 0);
+    assert_eq!(token.rarity, None);
+    let token_ids = vec!["1".to_string(), "2".to_string(), "3".to_string()];
+    let err = ExecHandler::execute_roll_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr2", &[]),
+        token_ids.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    // once the round is published, anyone can roll the traits, using the tiers in force at mint
+    deps.querier.update_terrand_round(2);
+    let update = ConfigUpdate {
+        rarity_tiers: Some(vec![tier("junk", 1, 1, 1)]),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    let res = ExecHandler::execute_roll_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr2", &[]),
+        token_ids,
+    )
+    .unwrap();
+    let attribute = |key: &str| {
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .unwrap()
+            .value
+            .split(',')
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>()
+    };
+    let rarities = attribute("rarity");
+    let staminas = attribute("stamina");
+    assert_eq!(rarities.len(), 3);
+
+    // every intern rolls its own traits within its tier's stamina range
+    for (i, token_id) in attribute("token_ids").iter().enumerate() {
+        let token = QueryHandler::query_intern_nft_info(deps.as_ref(), token_id.clone()).unwrap();
+        assert_eq!(token.extension.stamina.to_string(), staminas[i]);
+        assert_eq!(token.rarity.as_ref(), Some(&rarities[i]));
+        let range = match rarities[i].as_str() {
+            "common" => 50..=100,
+            "legendary" => 200..=250,
+            rarity => panic!("unexpected rarity {}", rarity),
+        };
+        assert!(range.contains(&token.extension.stamina));
+    }
+
+    // traits are only rolled once
+    let err = ExecHandler::execute_roll_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        vec!["1".to_string()],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingRoll {});
+}
+
+#[test]
+fn delayed_reveal() {
+    let mut deps = mock_dependencies(&[]);
+    let provenance_hash =
+        "5c2a1a8a9e1a7b3c0d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4".to_string();
+
+    let mut config = mock_config();
+    config.terrand_addr = Some(TERRAND.to_string());
+    config.placeholder = Some(PlaceholderMetadata {
+        name: None,
+        description: Some("an intern yet to be revealed".to_string()),
+        image: Some("ipfs://placeholder.png".to_string()),
+    });
+
+    // the provenance hash has to be a sha256 hash
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            config: config.clone(),
+            provenance_hash: Some("not a hash".to_string()),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("provenance hash must be a hex encoded sha256 hash")
+    );
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            config,
+            provenance_hash: Some(provenance_hash.to_uppercase()),
+        },
+    )
+    .unwrap();
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+
+    let res = QueryHandler::query_provenance(deps.as_ref()).unwrap();
+    assert_eq!(res.provenance_hash, Some(provenance_hash.clone()));
+    assert!(!res.revealed);
+    assert_eq!(res.offset, None);
+
+    // the placeholder is shown until the reveal
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.name, "intern #1");
+    assert_eq!(token.description, "an intern yet to be revealed");
+    assert_eq!(token.image, Some("ipfs://placeholder.png".to_string()));
+    let info =
+        QueryHandler::query_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string()).unwrap();
+    assert_eq!(
+        info.extension.image,
+        Some("ipfs://placeholder.png".to_string())
+    );
+
+    // the supply the committed ordering wraps around is frozen
+    let update = ConfigUpdate {
+        token_supply: Some(5000),
+        ..ConfigUpdate::default()
+    };
+    let err = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update)
+        .unwrap_err();
+    assert_eq!(err, ContractError::SupplyFrozen {});
+
+    let err = ExecHandler::execute_reveal(deps.as_mut(), mock_env(), mock_info(NONOWNER, &[]))
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = ExecHandler::execute_finalize_reveal(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::NoRevealRequested {});
+
+    // the owner commits to a round that isn't published yet, and can't request another one
+    deps.querier.update_terrand_round(1);
+    let res =
+        ExecHandler::execute_reveal(deps.as_mut(), mock_env(), mock_info(OWNER, &[])).unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "reveal_round" && attr.value == "2"));
+    let err =
+        ExecHandler::execute_reveal(deps.as_mut(), mock_env(), mock_info(OWNER, &[])).unwrap_err();
+    assert_eq!(err, ContractError::RevealRequested {});
+    let err = ExecHandler::execute_finalize_reveal(deps.as_mut()).unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+    let res = QueryHandler::query_provenance(deps.as_ref()).unwrap();
+    assert!(!res.revealed);
+    assert_eq!(res.reveal_round, Some(2));
+
+    // once it's published, anyone can finalize the reveal
+    deps.querier.update_terrand_round(2);
+    let res = ExecHandler::execute_finalize_reveal(deps.as_mut()).unwrap();
+    let offset = res
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "offset")
+        .unwrap()
+        .value
+        .parse::<u64>()
+        .unwrap();
+    assert!(offset < 10000);
+
+    let res = QueryHandler::query_provenance(deps.as_ref()).unwrap();
+    assert_eq!(res.provenance_hash, Some(provenance_hash));
+    assert!(res.revealed);
+    assert_eq!(res.offset, Some(offset));
+
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.description, "");
+    assert_eq!(token.image, None);
+
+    // the offset can only be drawn once
+    let err =
+        ExecHandler::execute_reveal(deps.as_mut(), mock_env(), mock_info(OWNER, &[])).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyRevealed {});
+    let err = ExecHandler::execute_finalize_reveal(deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyRevealed {});
+}
+
+#[test]
+fn burn() {
+    let mut deps = mock_dependencies(&[Coin::new(300, "uluna")]);
+    setup_contract(deps.as_mut(), Some(Coin::new(100, "uluna")), Some(4), None);
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(100, "uluna")]),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // only the owner of the intern (or an approved spender) can burn it
+    let err = ExecHandler::execute_burn(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr2", &[]),
+        "1".to_string(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // without a redemption configured, nothing is set aside and burning pays nothing
+    let res = ExecHandler::execute_burn(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        "1".to_string(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 0);
+    assert!(tokens()
+        .may_load(&deps.storage, "intern #1")
+        .unwrap()
+        .is_none());
+
+    // redemptions can't pay out more than the mint fee
+    let update = ConfigUpdate {
+        burn_redemption: Some(Some(Decimal::percent(150))),
+        ..ConfigUpdate::default()
+    };
+    let err = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("burn redemption cannot exceed 100%"))
+    );
+
+    // later mints set half of their fee aside
+    let update = ConfigUpdate {
+        burn_redemption: Some(Some(Decimal::percent(50))),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(200, "uluna")]),
+        Some(2),
+        None,
+    )
+    .unwrap();
+    let res =
+        QueryHandler::query_num_tokens_for_owner(deps.as_ref(), NONOWNER.to_string()).unwrap();
+    assert_eq!(res.count, 2);
+
+    // an approved spender gets the burner's share of the set aside fees
+    ExecHandler::cw721_base_execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Approve {
+            spender: "addr2".to_string(),
+            token_id: "3".to_string(),
+            expires: None,
+        },
+    )
+    .unwrap();
+    let res = ExecHandler::execute_burn(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr2", &[]),
+        "3".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            to_address: "addr2".to_string(),
+            amount: vec![Coin::new(50, "uluna")],
+        }
+        .into()
+    );
+
+    // the pool is paid out in the mint fee denom, which is locked while anything is reserved
+    let update = ConfigUpdate {
+        mint_fee: Some(Coin::new(100, "uusd")),
+        ..ConfigUpdate::default()
+    };
+    let err = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "mint fee denom cannot change while burn redemptions are reserved"
+        ))
+    );
+
+    // the rest of the pool stays reserved for the remaining intern
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![Coin::new(250, "uluna")]);
+    let err = ExecHandler::execute_withdraw(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        vec![Coin::new(250, "uluna")],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::RedemptionReserved {});
+    ExecHandler::execute_update_payees(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        vec![Payee {
+            address: "team".to_string(),
+            shares: 1,
+        }],
+    )
+    .unwrap();
+    let res = ExecHandler::execute_distribute(deps.as_mut(), mock_env()).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            to_address: "team".to_string(),
+            amount: vec![Coin::new(200, "uluna")],
+        }
+        .into()
+    );
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, vec![Coin::new(50, "uluna")]);
+    let res = ExecHandler::execute_burn(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        "2".to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            to_address: NONOWNER.to_string(),
+            amount: vec![Coin::new(50, "uluna")],
+        }
+        .into()
+    );
+
+    let res = QueryHandler::query_burned_tokens(deps.as_ref(), None, None).unwrap();
+    assert_eq!(
+        res.tokens,
+        vec!["1".to_string(), "2".to_string(), "3".to_string()]
+    );
+    let res =
+        QueryHandler::query_burned_tokens(deps.as_ref(), Some("1".to_string()), None).unwrap();
+    assert_eq!(res.tokens, vec!["2".to_string(), "3".to_string()]);
+
+    // burned ids are never reissued, and burned interns still count against the supply
+    let res = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(100, "uluna")]),
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_ids" && attr.value == "4"));
+    let err = ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(100, "uluna")]),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::SupplyExhausted {});
+}
+
+#[test]
+fn royalties() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        Some(2),
+        None,
+    )
+    .unwrap();
+
+    let res = QueryHandler::query_check_royalties(deps.as_ref()).unwrap();
+    assert!(!res.royalty_payments);
+    let res = QueryHandler::query_royalty_info(deps.as_ref(), "1".to_string(), Uint128::new(1000))
+        .unwrap();
+    assert_eq!(res.address, "");
+    assert_eq!(res.royalty_amount, Uint128::zero());
+
+    // royalties are capped at 100%
+    let update = ConfigUpdate {
+        royalty: Some(Some(Royalty {
+            payment_address: "artist".to_string(),
+            basis_points: 10_001,
+        })),
+        ..ConfigUpdate::default()
+    };
+    let err = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "royalty cannot exceed 10000 basis points"
+        ))
+    );
+
+    let update = ConfigUpdate {
+        royalty: Some(Some(Royalty {
+            payment_address: "artist".to_string(),
+            basis_points: 550,
+        })),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    // only the contract owner can override the royalty of a token
+    let collab = Royalty {
+        payment_address: "collab".to_string(),
+        basis_points: 1000,
+    };
+    let err = ExecHandler::execute_update_token_royalty(
+        deps.as_mut(),
+        mock_info(NONOWNER, &[]),
+        "2".to_string(),
+        Some(collab.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    ExecHandler::execute_update_token_royalty(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        "2".to_string(),
+        Some(collab),
+    )
+    .unwrap();
+
+    let res = QueryHandler::query_check_royalties(deps.as_ref()).unwrap();
+    assert!(res.royalty_payments);
+    let res = QueryHandler::query_royalty_info(deps.as_ref(), "1".to_string(), Uint128::new(1000))
+        .unwrap();
+    assert_eq!(res.address, "artist");
+    assert_eq!(res.royalty_amount, Uint128::new(55));
+    let res = QueryHandler::query_royalty_info(deps.as_ref(), "2".to_string(), Uint128::new(1000))
+        .unwrap();
+    assert_eq!(res.address, "collab");
+    assert_eq!(res.royalty_amount, Uint128::new(100));
+
+    // the royalty is part of the token metadata
+    let info =
+        QueryHandler::query_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string()).unwrap();
+    assert_eq!(info.extension.royalty_percentage, Some(5));
+    assert_eq!(
+        info.extension.royalty_payment_address,
+        Some("artist".to_string())
+    );
+
+    // clearing the override falls back to the collection royalty
+    ExecHandler::execute_update_token_royalty(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        "2".to_string(),
+        None,
+    )
+    .unwrap();
+    let res = QueryHandler::query_royalty_info(deps.as_ref(), "2".to_string(), Uint128::new(1000))
+        .unwrap();
+    assert_eq!(res.address, "artist");
+    assert_eq!(res.royalty_amount, Uint128::new(55));
+}
+
+#[test]
+fn staking_round_trip() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        None,
+        None,
+    )
+    .unwrap();
+
+    // the staking contract is sent the numeric token id, and looks the intern up by it
+    let res = ExecHandler::cw721_base_execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::SendNft {
+            contract: "staking_contract".to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&Cw721HookMsg::Stake {
+                staking_type: "exp".to_string(),
+            })
+            .unwrap(),
+        },
+    )
+    .unwrap();
+    let receive = match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+            match from_binary::<StakingExecuteMsg>(msg).unwrap() {
+                StakingExecuteMsg::Receive(receive) => receive,
+                msg => panic!("unexpected message {:?}", msg),
+            }
+        }
+        msg => panic!("unexpected message {:?}", msg),
+    };
+    let token_id = receive.token_id;
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), token_id.clone()).unwrap();
+    assert_eq!(token.owner, "staking_contract");
+
+    // unstaking adjusts the traits and hands the intern back by the same number
+    ExecHandler::execute_adjust_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_contract", &[]),
+        token_id.clone(),
+        TraitDeltas {
+            experience: 10,
+            ..TraitDeltas::default()
+        },
+        None,
+        Some("unstake exp".to_string()),
+    )
+    .unwrap();
+    ExecHandler::cw721_base_execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_contract", &[]),
+        ExecuteMsg::TransferNft {
+            recipient: NONOWNER.to_string(),
+            token_id: token_id.clone(),
+        },
+    )
+    .unwrap();
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), token_id).unwrap();
+    assert_eq!(token.owner, NONOWNER);
+    assert_eq!(token.extension.experience, 10);
+}
+
+#[test]
+fn token_uris() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    let update = ConfigUpdate {
+        base_uri: Some(Some("ipfs://metadata".to_string())),
+        image_base_uri: Some(Some("ipfs://images".to_string())),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+
+    let info =
+        QueryHandler::query_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string()).unwrap();
+    assert_eq!(info.token_uri, Some("ipfs://metadata/1".to_string()));
+    assert_eq!(info.extension.image, Some("ipfs://images/1".to_string()));
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.image, Some("ipfs://images/1".to_string()));
+    assert_eq!(token.token_uri, Some("ipfs://metadata/1".to_string()));
+
+    // only the contract owner can give an intern its own image
+    let err = ExecHandler::execute_update_token_image(
+        deps.as_mut(),
+        mock_info(NONOWNER, &[]),
+        "1".to_string(),
+        Some("ipfs://custom.png".to_string()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    ExecHandler::execute_update_token_image(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        "1".to_string(),
+        Some("ipfs://custom.png".to_string()),
+    )
+    .unwrap();
+    let info =
+        QueryHandler::query_all_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string(), false)
+            .unwrap();
+    assert_eq!(info.info.token_uri, Some("ipfs://metadata/1".to_string()));
+    assert_eq!(
+        info.info.extension.image,
+        Some("ipfs://custom.png".to_string())
+    );
+}
+
+#[test]
+fn render_svg() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    let update = ConfigUpdate {
+        base_uri: Some(Some("ipfs://metadata".to_string())),
+        render_svg: Some(true),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+
+    let info =
+        QueryHandler::query_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string()).unwrap();
+    let svg = info.extension.image_data.clone().unwrap();
+    assert!(svg.contains("experience 0"));
+
+    // the token uri embeds the whole metadata, svg included
+    let token_uri = info.token_uri.unwrap();
+    let encoded = token_uri
+        .strip_prefix("data:application/json;base64,")
+        .unwrap();
+    let metadata: Cw721Metadata = from_slice(&base64::decode(encoded).unwrap()).unwrap();
+    assert_eq!(metadata, info.extension);
+
+    // the art follows the traits as they change
+    ExecHandler::execute_update_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_contract", &[]),
+        "1".to_string(),
+        InternExtension {
+            experience: 120,
+            gold: 30,
+            stamina: 90,
+        },
+        None,
+    )
+    .unwrap();
+    let info =
+        QueryHandler::query_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string()).unwrap();
+    let svg = info.extension.image_data.unwrap();
+    assert!(svg.contains("experience 120"));
+    assert!(svg.contains("gold 30"));
+    assert!(svg.contains("stamina 90"));
+}
+
+#[test]
+fn levels() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    // thresholds have to increase
+    let update = ConfigUpdate {
+        level_curve: Some(LevelCurve {
+            thresholds: vec![300, 100],
+        }),
+        ..ConfigUpdate::default()
+    };
+    let err = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "level thresholds must be positive and increasing"
+        ))
+    );
+
+    let update = ConfigUpdate {
+        level_curve: Some(LevelCurve {
+            thresholds: vec![100, 300],
+        }),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.level, 1);
+    assert_eq!(token.next_level_experience, Some(100));
+    assert_eq!(token.level_progress, Decimal::zero());
+
+    let update_experience = |deps: DepsMut, exp: u64| {
+        ExecHandler::execute_update_traits(
+            deps,
+            mock_env(),
+            mock_info("staking_contract", &[]),
+            "1".to_string(),
+            InternExtension {
+                experience: exp,
+                gold: 0,
+                stamina: 100,
+            },
+            None,
+        )
+        .unwrap()
+    };
+
+    // crossing a threshold levels the intern up
+    let res = update_experience(deps.as_mut(), 150);
+    assert_eq!(res.events.len(), 1);
+    assert_eq!(res.events[0].ty, "level_up");
+    assert_eq!(res.events[0].attributes[0].value, "1");
+    assert_eq!(res.events[0].attributes[1].value, "2");
+
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.level, 2);
+    assert_eq!(token.next_level_experience, Some(300));
+    assert_eq!(token.level_progress, Decimal::percent(25));
+    let info =
+        QueryHandler::query_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string()).unwrap();
+    let attributes = info.extension.attributes.unwrap();
+    assert_eq!(attributes[0].trait_type, "level");
+    assert_eq!(attributes[0].value, "2");
+
+    let res = update_experience(deps.as_mut(), 200);
+    assert!(res.events.is_empty());
+
+    // the response still parses as the plain token info
+    let binary =
+        to_binary(&QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap())
+            .unwrap();
+    let token: InternTokenInfo = from_binary(&binary).unwrap();
+    assert_eq!(token.extension.experience, 200);
+}
+
+#[test]
+fn gold_conversion() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        None,
+        None,
+    )
+    .unwrap();
+    ExecHandler::execute_update_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_contract", &[]),
+        "1".to_string(),
+        InternExtension {
+            experience: 0,
+            gold: 50,
+            stamina: 100,
+        },
+        None,
+    )
+    .unwrap();
+
+    let err = ExecHandler::execute_withdraw_gold(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        "1".to_string(),
+        20,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::GoldDisabled {});
+
+    let update = ConfigUpdate {
+        gold_token: Some(Some(GoldToken {
+            address: "gold".to_string(),
+            rate: Uint128::new(1000),
+        })),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    // only the owner of the intern can withdraw its gold
+    let err = ExecHandler::execute_withdraw_gold(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        "1".to_string(),
+        20,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = ExecHandler::execute_withdraw_gold(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        "1".to_string(),
+        20,
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: "gold".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: NONOWNER.to_string(),
+                amount: Uint128::new(20_000),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.extension.gold, 30);
+
+    let err = ExecHandler::execute_withdraw_gold(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        "1".to_string(),
+        40,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InsufficientGold {});
+
+    // only the gold token can deposit gold
+    let deposit = |amount: u128| Cw20ReceiveMsg {
+        sender: NONOWNER.to_string(),
+        amount: Uint128::new(amount),
+        msg: to_binary(&GoldHookMsg::Deposit {
+            token_id: "1".to_string(),
+        })
+        .unwrap(),
+    };
+    let err = ExecHandler::execute_receive(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("fake", &[]),
+        deposit(10_000),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = ExecHandler::execute_receive(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("gold", &[]),
+        deposit(10_500),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidGoldAmount {});
+
+    // deposited tokens are burned into gold
+    let res = ExecHandler::execute_receive(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("gold", &[]),
+        deposit(10_000),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        WasmMsg::Execute {
+            contract_addr: "gold".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn {
+                amount: Uint128::new(10_000),
+            })
+            .unwrap(),
+            funds: vec![],
+        }
+        .into()
+    );
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.extension.gold, 40);
+
+    // gold moving in and out of the intern shows up in its trait history
+    let res =
+        QueryHandler::query_trait_history(deps.as_ref(), "1".to_string(), Some(1), None).unwrap();
+    let gold_changes: Vec<_> = res
+        .changes
+        .iter()
+        .map(|change| {
+            (
+                change.updater.as_str(),
+                change.old.gold,
+                change.new.gold,
+                change.reason.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        gold_changes,
+        vec![
+            (NONOWNER, 50, 30, Some("withdraw gold")),
+            (NONOWNER, 30, 40, Some("deposit gold")),
+        ]
+    );
+}
+
+#[test]
+fn trait_updaters() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+
+    let update_traits = |deps: DepsMut, sender: &str, exp: u64, gold: u64, stamina: u64| {
+        ExecHandler::execute_update_traits(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            "1".to_string(),
+            InternExtension {
+                experience: exp,
+                gold,
+                stamina,
+            },
+            None,
+        )
+    };
+
+    // unregistered contracts can't update traits
+    let err = update_traits(deps.as_mut(), "quests", 0, 10, 100).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // only the contract owner can register updaters
+    let err = ExecHandler::execute_add_updater(
+        deps.as_mut(),
+        mock_info(NONOWNER, &[]),
+        "quests".to_string(),
+        vec![InternTrait::Gold],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    ExecHandler::execute_add_updater(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        "quests".to_string(),
+        vec![InternTrait::Gold],
+    )
+    .unwrap();
+    ExecHandler::execute_add_updater(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        "arena".to_string(),
+        vec![InternTrait::Experience, InternTrait::Stamina],
+    )
+    .unwrap();
+
+    let res = QueryHandler::query_updaters(deps.as_ref(), None, None).unwrap();
+    assert_eq!(
+        res.updaters,
+        vec![
+            TraitUpdater {
+                address: "arena".to_string(),
+                traits: vec![InternTrait::Experience, InternTrait::Stamina],
+            },
+            TraitUpdater {
+                address: "quests".to_string(),
+                traits: vec![InternTrait::Gold],
+            },
+        ]
+    );
+
+    // updaters can only change the traits they were granted
+    update_traits(deps.as_mut(), "quests", 0, 10, 100).unwrap();
+    let err = update_traits(deps.as_mut(), "quests", 0, 20, 90).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TraitNotPermitted {
+            name: "stamina".to_string()
+        }
+    );
+    update_traits(deps.as_mut(), "arena", 5, 10, 90).unwrap();
+
+    // the staking contract can still change every trait
+    update_traits(deps.as_mut(), "staking_contract", 6, 11, 80).unwrap();
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(
+        token.extension,
+        InternExtension {
+            experience: 6,
+            gold: 11,
+            stamina: 80,
+        }
+    );
+
+    ExecHandler::execute_remove_updater(deps.as_mut(), mock_info(OWNER, &[]), "quests".to_string())
+        .unwrap();
+    let err = update_traits(deps.as_mut(), "quests", 6, 20, 80).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let res = QueryHandler::query_updaters(deps.as_ref(), None, None).unwrap();
+    assert_eq!(res.updaters.len(), 1);
+}
+
+#[test]
+fn adjust_traits() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+    ExecHandler::execute_add_updater(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        "quests".to_string(),
+        vec![InternTrait::Gold],
+    )
+    .unwrap();
+
+    let adjust_traits =
+        |deps: DepsMut, sender: &str, deltas: TraitDeltas, expected: Option<InternExtension>| {
+            ExecHandler::execute_adjust_traits(
+                deps,
+                mock_env(),
+                mock_info(sender, &[]),
+                "1".to_string(),
+                deltas,
+                expected,
+                None,
+            )
+        };
+
+    // adjustments from different contracts add up
+    adjust_traits(
+        deps.as_mut(),
+        "staking_contract",
+        TraitDeltas {
+            experience: 10,
+            gold: 50,
+            stamina: 0,
+        },
+        None,
+    )
+    .unwrap();
+    adjust_traits(
+        deps.as_mut(),
+        "quests",
+        TraitDeltas {
+            gold: -20,
+            ..TraitDeltas::default()
+        },
+        None,
+    )
+    .unwrap();
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(
+        token.extension,
+        InternExtension {
+            experience: 10,
+            gold: 30,
+            stamina: 100,
+        }
+    );
+
+    // traits can't go below zero
+    let err = adjust_traits(
+        deps.as_mut(),
+        "quests",
+        TraitDeltas {
+            gold: -31,
+            ..TraitDeltas::default()
+        },
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TraitOutOfRange {
+            name: "gold".to_string()
+        }
+    );
+
+    // updaters are still limited to their traits
+    let err = adjust_traits(
+        deps.as_mut(),
+        "quests",
+        TraitDeltas {
+            stamina: -10,
+            ..TraitDeltas::default()
+        },
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::TraitNotPermitted {
+            name: "stamina".to_string()
+        }
+    );
+
+    // the guard rejects adjustments computed from stale values
+    let stale = InternExtension {
+        experience: 10,
+        gold: 50,
+        stamina: 100,
+    };
+    let err = adjust_traits(
+        deps.as_mut(),
+        "quests",
+        TraitDeltas {
+            gold: -5,
+            ..TraitDeltas::default()
+        },
+        Some(stale),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TraitMismatch {});
+    adjust_traits(
+        deps.as_mut(),
+        "quests",
+        TraitDeltas {
+            gold: -5,
+            ..TraitDeltas::default()
+        },
+        Some(token.extension),
+    )
+    .unwrap();
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.extension.gold, 25);
+}
+
+#[test]
+fn trait_history() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+        .unwrap();
+    ExecHandler::execute_add_updater(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        "quests".to_string(),
+        vec![InternTrait::Gold],
+    )
+    .unwrap();
+
+    let mut env = mock_env();
+    env.block.height = 100;
+    ExecHandler::execute_adjust_traits(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("staking_contract", &[]),
+        "1".to_string(),
+        TraitDeltas {
+            experience: 10,
+            gold: 50,
+            stamina: 0,
+        },
+        None,
+        Some("unstake".to_string()),
+    )
+    .unwrap();
+    env.block.height = 200;
+    ExecHandler::execute_adjust_traits(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("quests", &[]),
+        "1".to_string(),
+        TraitDeltas {
+            gold: -20,
+            ..TraitDeltas::default()
+        },
+        None,
+        None,
+    )
+    .unwrap();
+
+    // rejected updates leave no trace
+    ExecHandler::execute_adjust_traits(
+        deps.as_mut(),
+        env,
+        mock_info("quests", &[]),
+        "1".to_string(),
+        TraitDeltas {
+            stamina: -10,
+            ..TraitDeltas::default()
+        },
+        None,
+        None,
+    )
+    .unwrap_err();
+
+    let res =
+        QueryHandler::query_trait_history(deps.as_ref(), "1".to_string(), None, None).unwrap();
+    assert_eq!(
+        res.changes,
+        vec![
+            TraitChange {
+                id: 1,
+                updater: "staking_contract".to_string(),
+                block_height: 100,
+                old: InternExtension {
+                    experience: 0,
+                    gold: 0,
+                    stamina: 100,
+                },
+                new: InternExtension {
+                    experience: 10,
+                    gold: 50,
+                    stamina: 100,
+                },
+                reason: Some("unstake".to_string()),
+            },
+            TraitChange {
+                id: 2,
+                updater: "quests".to_string(),
+                block_height: 200,
+                old: InternExtension {
+                    experience: 10,
+                    gold: 50,
+                    stamina: 100,
+                },
+                new: InternExtension {
+                    experience: 10,
+                    gold: 30,
+                    stamina: 100,
+                },
+                reason: None,
+            },
+        ]
+    );
+
+    // paginates by change id
+    let res =
+        QueryHandler::query_trait_history(deps.as_ref(), "1".to_string(), Some(1), None).unwrap();
+    assert_eq!(res.changes.len(), 1);
+    assert_eq!(res.changes[0].id, 2);
+    let res =
+        QueryHandler::query_trait_history(deps.as_ref(), "1".to_string(), None, Some(1)).unwrap();
+    assert_eq!(res.changes.len(), 1);
+    assert_eq!(res.changes[0].id, 1);
+}
+
+#[test]
+fn revenue_split() {
+    let mut deps = mock_dependencies(&[Coin::new(1000, "uluna"), Coin::new(10, "uusd")]);
+    setup_contract(deps.as_mut(), None, None, None);
+    let update_payees = |deps: DepsMut, sender: &str, payees: &[(&str, u64)]| {
+        let payees = payees
+            .iter()
+            .map(|(address, shares)| Payee {
+                address: address.to_string(),
+                shares: *shares,
+            })
+            .collect();
+        ExecHandler::execute_update_payees(deps, mock_env(), mock_info(sender, &[]), payees)
+    };
+    let payment = |address: &str, amount: Vec<Coin>| -> CosmosMsg {
+        BankMsg::Send {
+            to_address: address.to_string(),
+            amount,
+        }
+        .into()
+    };
+
+    // only the owner can update payees, which need positive shares and distinct addresses
+    let err = update_payees(deps.as_mut(), NONOWNER, &[("team", 3)]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    let err = update_payees(deps.as_mut(), OWNER, &[("team", 0)]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("payee shares must be positive"))
+    );
+    let err = update_payees(deps.as_mut(), OWNER, &[("team", 1), ("team", 2)]).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("payees must be unique"))
+    );
+    let err = ExecHandler::execute_distribute(deps.as_mut(), mock_env()).unwrap_err();
+    assert_eq!(err, ContractError::NoPayees {});
+
+    let res = update_payees(deps.as_mut(), OWNER, &[("team", 3), ("artist", 1)]).unwrap();
+    assert_eq!(res.messages.len(), 0);
+
+    // the owner can no longer withdraw everything
+    let err = ExecHandler::execute_withdraw(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        vec![Coin::new(100, "uluna")],
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PayeesConfigured {});
+
+    // anyone can distribute the balance by shares
+    let res = ExecHandler::execute_distribute(deps.as_mut(), mock_env()).unwrap();
+    let messages: Vec<_> = res.messages.into_iter().map(|msg| msg.msg).collect();
+    assert_eq!(
+        messages,
+        vec![
+            payment("team", vec![Coin::new(750, "uluna"), Coin::new(7, "uusd")]),
+            payment(
+                "artist",
+                vec![Coin::new(250, "uluna"), Coin::new(2, "uusd")]
+            ),
+        ]
+    );
+
+    // later revenue is split on top of what was already paid out
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        vec![Coin::new(400, "uluna"), Coin::new(1, "uusd")],
+    );
+    let res = QueryHandler::query_payees(deps.as_ref(), mock_env()).unwrap();
+    assert_eq!(
+        res.payees,
+        vec![
+            PayeeInfo {
+                address: "team".to_string(),
+                shares: 3,
+                released: vec![Coin::new(750, "uluna"), Coin::new(7, "uusd")],
+                pending: vec![Coin::new(300, "uluna")],
+            },
+            PayeeInfo {
+                address: "artist".to_string(),
+                shares: 1,
+                released: vec![Coin::new(250, "uluna"), Coin::new(2, "uusd")],
+                pending: vec![Coin::new(100, "uluna")],
+            },
+        ]
+    );
+
+    // changing payees settles up with the current ones first
+    let res = update_payees(deps.as_mut(), OWNER, &[("team", 1), ("community", 1)]).unwrap();
+    let messages: Vec<_> = res.messages.into_iter().map(|msg| msg.msg).collect();
+    assert_eq!(
+        messages,
+        vec![
+            payment("team", vec![Coin::new(300, "uluna")]),
+            payment("artist", vec![Coin::new(100, "uluna")]),
+        ]
+    );
+    deps.querier.update_balance(
+        MOCK_CONTRACT_ADDR,
+        vec![Coin::new(200, "uluna"), Coin::new(1, "uusd")],
+    );
+    let res = ExecHandler::execute_distribute(deps.as_mut(), mock_env()).unwrap();
+    let messages: Vec<_> = res.messages.into_iter().map(|msg| msg.msg).collect();
+    assert_eq!(
+        messages,
+        vec![
+            payment("team", vec![Coin::new(100, "uluna")]),
+            payment("community", vec![Coin::new(100, "uluna")]),
+        ]
+    );
+}
+
+#[test]
+fn transfer_wallet_limit() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, Some(2));
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr1", &[]),
+        Some(2),
+        None,
+    )
+    .unwrap();
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        None,
+        None,
+    )
+    .unwrap();
+    let transfer = |deps: DepsMut, sender: &str, recipient: &str, token_id: &str| {
+        ExecHandler::cw721_base_execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: token_id.to_string(),
+            },
+        )
+    };
+
+    // transfers aren't limited by default
+    transfer(deps.as_mut(), NONOWNER, "addr1", "3").unwrap();
+    transfer(deps.as_mut(), "addr1", NONOWNER, "3").unwrap();
+
+    let update = ConfigUpdate {
+        transfer_wallet_limit: Some(true),
+        wallet_limit_exempt: Some(vec!["market".to_string()]),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    // transfers and sends can't take a wallet over the limit
+    let err = transfer(deps.as_mut(), NONOWNER, "addr1", "3").unwrap_err();
+    assert_eq!(err, ContractError::WalletLimit {});
+    let err = ExecHandler::cw721_base_execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::SendNft {
+            contract: "addr1".to_string(),
+            token_id: "3".to_string(),
+            msg: Binary::default(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::WalletLimit {});
+
+    // exempt contracts and the staking contract can hold any number of interns
+    transfer(deps.as_mut(), "addr1", "market", "1").unwrap();
+    transfer(deps.as_mut(), "addr1", "market", "2").unwrap();
+    transfer(deps.as_mut(), NONOWNER, "market", "3").unwrap();
+    for token_id in &["1", "2", "3"] {
+        transfer(deps.as_mut(), "market", "staking_contract", token_id).unwrap();
+    }
+    let res =
+        QueryHandler::query_num_tokens_for_owner(deps.as_ref(), "staking_contract".to_string())
+            .unwrap();
+    assert_eq!(res.count, 3);
+
+    // interns coming back from staking or a marketplace can go over the limit, so a wallet
+    // that was refilled while an intern was staked can still unstake it
+    transfer(deps.as_mut(), "staking_contract", NONOWNER, "2").unwrap();
+    transfer(deps.as_mut(), "staking_contract", NONOWNER, "3").unwrap();
+    transfer(deps.as_mut(), "staking_contract", NONOWNER, "1").unwrap();
+    transfer(deps.as_mut(), NONOWNER, "market", "1").unwrap();
+    transfer(deps.as_mut(), "market", NONOWNER, "1").unwrap();
+    let res =
+        QueryHandler::query_num_tokens_for_owner(deps.as_ref(), NONOWNER.to_string()).unwrap();
+    assert_eq!(res.count, 3);
+}
+
+#[test]
+fn intern_tokens() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        Some(3),
+        None,
+    )
+    .unwrap();
+    ExecHandler::execute_set_profile(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        "1".to_string(),
+        Some("Bob".to_string()),
+        None,
+    )
+    .unwrap();
+
+    // entries carry their token id, which the next page starts after, even once renamed
+    let res =
+        QueryHandler::query_intern_tokens(deps.as_ref(), OWNER.to_string(), None, Some(2)).unwrap();
+    let ids: Vec<_> = res
+        .tokens
+        .iter()
+        .map(|entry| entry.token_id.clone())
+        .collect();
+    assert_eq!(ids, vec!["1".to_string(), "2".to_string()]);
+    assert_eq!(res.tokens[0].info.name, "Bob");
+    let res = QueryHandler::query_intern_tokens(
+        deps.as_ref(),
+        OWNER.to_string(),
+        Some(ids[1].clone()),
+        Some(2),
+    )
+    .unwrap();
+    let ids: Vec<_> = res
+        .tokens
+        .iter()
+        .map(|entry| entry.token_id.clone())
+        .collect();
+    assert_eq!(ids, vec!["3".to_string()]);
+
+    let res =
+        QueryHandler::query_all_intern_tokens(deps.as_ref(), Some("1".to_string()), None).unwrap();
+    let ids: Vec<_> = res
+        .tokens
+        .iter()
+        .map(|entry| entry.token_id.clone())
+        .collect();
+    assert_eq!(ids, vec!["2".to_string(), "3".to_string()]);
+    let err = QueryHandler::query_all_intern_tokens(deps.as_ref(), Some("Bob".to_string()), None)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("expected numeric token identifier")
+    );
+}
+
+#[test]
+fn set_profile() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    let update = ConfigUpdate {
+        profile_fee: Some(10),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    ExecHandler::execute_mint(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        Some(2),
+        None,
+    )
+    .unwrap();
+    for &(token_id, gold) in &[("1", 25), ("2", 10)] {
+        ExecHandler::execute_adjust_traits(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("staking_contract", &[]),
+            token_id.to_string(),
+            TraitDeltas {
+                gold,
+                ..TraitDeltas::default()
+            },
+            None,
+            None,
+        )
+        .unwrap();
+    }
+    let set_profile = |deps: DepsMut, sender: &str, token_id: &str, name: &str| {
+        ExecHandler::execute_set_profile(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            token_id.to_string(),
+            Some(name.to_string()),
+            None,
+        )
+    };
+
+    // only the owner of the intern can set its profile
+    let err = set_profile(deps.as_mut(), NONOWNER, "1", "Bob").unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // names and descriptions are validated
+    for name in &[
+        "",
+        " Bob",
+        "intern #2",
+        "Bobby Tables; DROP",
+        &"a".repeat(33),
+    ] {
+        let err = set_profile(deps.as_mut(), OWNER, "1", name).unwrap_err();
+        assert_eq!(err, ContractError::InvalidName {});
+    }
+    let err = ExecHandler::execute_set_profile(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        "1".to_string(),
+        None,
+        Some("line\nbreak".to_string()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDescription {});
+
+    // setting a profile costs gold, and shows up in the metadata
+    ExecHandler::execute_set_profile(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        "1".to_string(),
+        Some("Bob".to_string()),
+        Some("Keeps the coffee flowing".to_string()),
+    )
+    .unwrap();
+    let res =
+        QueryHandler::query_nft_info(deps.as_ref(), mock_env(), "intern #1".to_string()).unwrap();
+    assert_eq!(res.extension.name, Some("Bob".to_string()));
+    assert_eq!(
+        res.extension.description,
+        Some("Keeps the coffee flowing".to_string())
+    );
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "1".to_string()).unwrap();
+    assert_eq!(token.extension.gold, 15);
+    let res =
+        QueryHandler::query_trait_history(deps.as_ref(), "1".to_string(), None, None).unwrap();
+    let change = res.changes.last().unwrap();
+    assert_eq!(change.updater, OWNER);
+    assert_eq!(change.new.gold, 15);
+    assert_eq!(change.reason, Some("profile fee".to_string()));
+
+    // names are unique regardless of case, but an intern can change the case of its own
+    let err = set_profile(deps.as_mut(), OWNER, "2", "BOB").unwrap_err();
+    assert_eq!(err, ContractError::NameTaken {});
+    set_profile(deps.as_mut(), OWNER, "1", "bob").unwrap();
+    let err = set_profile(deps.as_mut(), OWNER, "1", "Robert").unwrap_err();
+    assert_eq!(err, ContractError::InsufficientGold {});
+
+    // the fee isn't charged for an update that changes nothing
+    let err = ExecHandler::execute_set_profile(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        "2".to_string(),
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::EmptyProfile {});
+
+    // paying the fee moves gold, so it stops along with other trait updates
+    ExecHandler::execute_set_paused(deps.as_mut(), mock_info(OWNER, &[]), None, None, Some(true))
+        .unwrap();
+    let err = set_profile(deps.as_mut(), OWNER, "2", "Robert").unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Paused {
+            operation: "trait updates".to_string()
+        }
+    );
+    ExecHandler::execute_set_paused(
+        deps.as_mut(),
+        mock_info(OWNER, &[]),
+        None,
+        None,
+        Some(false),
+    )
+    .unwrap();
+
+    // burning an intern frees up its name
+    ExecHandler::execute_burn(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        "1".to_string(),
+    )
+    .unwrap();
+    set_profile(deps.as_mut(), OWNER, "2", "Bob").unwrap();
+    let token = QueryHandler::query_intern_nft_info(deps.as_ref(), "2".to_string()).unwrap();
+    assert_eq!(token.name, "Bob");
+    assert_eq!(token.extension.gold, 0);
+}
+
+#[test]
+fn leaderboard() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, Some(20));
+    for _ in 0..11 {
+        ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+            .unwrap();
+    }
+    let traits = &[
+        ("1", 30, 5),
+        ("2", 10, 50),
+        ("3", 30, 0),
+        ("4", 20, 5),
+        ("10", 10, 5),
+        ("11", 30, 0),
+    ];
+    for &(token_id, experience, gold) in traits {
+        ExecHandler::execute_adjust_traits(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("staking_contract", &[]),
+            token_id.to_string(),
+            TraitDeltas {
+                experience,
+                gold,
+                stamina: 0,
+            },
+            None,
+            None,
+        )
+        .unwrap();
+    }
+
+    let ranking = |deps: &cosmwasm_std::OwnedDeps<_, _, _>,
+                   intern_trait,
+                   start_after: Option<&str>,
+                   limit,
+                   order| {
+        QueryHandler::query_leaderboard(
+            deps.as_ref(),
+            intern_trait,
+            start_after.map(String::from),
+            limit,
+            order,
+        )
+        .unwrap()
+        .entries
+        .into_iter()
+        .map(|entry| entry.token_id)
+        .collect::<Vec<_>>()
+    };
+
+    // highest first by default, ties broken by numeric token id in the same direction
+    assert_eq!(
+        ranking(&deps, InternTrait::Experience, None, Some(20), None),
+        vec!["11", "3", "1", "4", "10", "2", "9", "8", "7", "6", "5"]
+    );
+    assert_eq!(
+        ranking(&deps, InternTrait::Experience, None, Some(2), None),
+        vec!["11", "3"]
+    );
+    assert_eq!(
+        ranking(&deps, InternTrait::Experience, Some("1"), Some(3), None),
+        vec!["4", "10", "2"]
+    );
+    assert_eq!(
+        ranking(
+            &deps,
+            InternTrait::Gold,
+            None,
+            Some(20),
+            Some(SortOrder::Ascending)
+        ),
+        vec!["3", "5", "6", "7", "8", "9", "11", "1", "4", "10", "2"]
+    );
+    assert_eq!(
+        ranking(
+            &deps,
+            InternTrait::Gold,
+            Some("9"),
+            Some(3),
+            Some(SortOrder::Ascending)
+        ),
+        vec!["11", "1", "4"]
+    );
+
+    // the entries carry the displayed token
+    let res =
+        QueryHandler::query_leaderboard(deps.as_ref(), InternTrait::Gold, None, Some(1), None)
+            .unwrap();
+    assert_eq!(res.entries[0].token.name, "intern #2");
+    assert_eq!(res.entries[0].token.extension.gold, 50);
+
+    // trait updates move interns up the board
+    ExecHandler::execute_adjust_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_contract", &[]),
+        "2".to_string(),
+        TraitDeltas {
+            experience: 25,
+            ..TraitDeltas::default()
+        },
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        ranking(&deps, InternTrait::Experience, None, Some(6), None),
+        vec!["2", "11", "3", "1", "4", "10"]
+    );
+
+    // stamina is not ranked
+    QueryHandler::query_leaderboard(deps.as_ref(), InternTrait::Stamina, None, None, None)
+        .unwrap_err();
+}
+
+#[test]
+fn collection_stats() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+    let curve = LevelCurve {
+        thresholds: vec![100, 300],
+    };
+    let update = ConfigUpdate {
+        level_curve: Some(curve.clone()),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert_eq!(stats.minted, 0);
+    assert_eq!(stats.remaining_supply, 10000);
+    assert_eq!(stats.holders, 0);
+    assert_eq!(stats.average_stamina, Decimal::zero());
+    assert_eq!(stats.levels, vec![]);
+
+    for _ in 0..3 {
+        ExecHandler::execute_mint(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), None, None)
+            .unwrap();
+    }
+    let transfer = |deps: DepsMut, sender: &str, recipient: &str| {
+        ExecHandler::cw721_base_execute(
+            deps,
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap()
+    };
+    transfer(deps.as_mut(), OWNER, NONOWNER);
+    ExecHandler::execute_adjust_traits(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("staking_contract", &[]),
+        "2".to_string(),
+        TraitDeltas {
+            experience: 150,
+            gold: 40,
+            stamina: 0,
+        },
+        None,
+        None,
+    )
+    .unwrap();
+    ExecHandler::execute_burn(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        "3".to_string(),
+    )
+    .unwrap();
+
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert_eq!(
+        stats,
+        CollectionStatsResponse {
+            minted: 3,
+            remaining_supply: 9997,
+            holders: 2,
+            total_experience: Uint128::new(150),
+            total_gold: Uint128::new(40),
+            total_stamina: Uint128::new(200),
+            average_experience: Decimal::from_ratio(75u128, 1u128),
+            average_gold: Decimal::from_ratio(20u128, 1u128),
+            average_stamina: Decimal::from_ratio(100u128, 1u128),
+            levels: vec![
+                LevelCount { level: 1, count: 1 },
+                LevelCount { level: 2, count: 1 },
+            ],
+            recounting_levels: false,
+        }
+    );
+
+    // holders are only counted once, however many interns they hold
+    transfer(deps.as_mut(), NONOWNER, OWNER);
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert_eq!(stats.holders, 1);
+
+    // the staking contract and marketplaces don't count as holders
+    let update = ConfigUpdate {
+        wallet_limit_exempt: Some(vec!["market".to_string()]),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    transfer(deps.as_mut(), OWNER, "staking_contract");
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert_eq!(stats.holders, 1);
+    transfer(deps.as_mut(), "staking_contract", "market");
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert_eq!(stats.holders, 1);
+    transfer(deps.as_mut(), "market", OWNER);
+
+    // setting the same curve keeps the level counts
+    let update = ConfigUpdate {
+        level_curve: Some(curve),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert!(!stats.recounting_levels);
+
+    // a new curve has the levels recounted, a page at a time
+    let update = ConfigUpdate {
+        level_curve: Some(LevelCurve {
+            thresholds: vec![50, 100],
+        }),
+        ..ConfigUpdate::default()
+    };
+    ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), update).unwrap();
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert!(stats.recounting_levels);
+    assert_eq!(stats.levels, vec![]);
+    assert_eq!(stats.total_experience, Uint128::new(150));
+
+    let err = ExecHandler::execute_recount_levels(deps.as_mut(), mock_info(NONOWNER, &[]), None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    ExecHandler::execute_recount_levels(deps.as_mut(), mock_info(OWNER, &[]), Some(1)).unwrap();
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert_eq!(stats.levels, vec![LevelCount { level: 1, count: 1 }]);
+
+    // interns that are already counted move between levels, the rest get counted later on
+    let gain_experience = |deps: DepsMut, token_id: &str| {
+        ExecHandler::execute_adjust_traits(
+            deps,
+            mock_env(),
+            mock_info("staking_contract", &[]),
+            token_id.to_string(),
+            TraitDeltas {
+                experience: 60,
+                ..TraitDeltas::default()
+            },
+            None,
+            None,
+        )
+        .unwrap()
+    };
+    gain_experience(deps.as_mut(), "1");
+    gain_experience(deps.as_mut(), "2");
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert_eq!(stats.levels, vec![LevelCount { level: 2, count: 1 }]);
+
+    let res =
+        ExecHandler::execute_recount_levels(deps.as_mut(), mock_info(OWNER, &[]), None).unwrap();
+    assert_eq!(res.attributes[1], attr("counted", "1"));
+    assert_eq!(res.attributes[2], attr("finished", "true"));
+    let stats = QueryHandler::query_collection_stats(deps.as_ref()).unwrap();
+    assert!(!stats.recounting_levels);
+    assert_eq!(
+        stats.levels,
+        vec![
+            LevelCount { level: 2, count: 1 },
+            LevelCount { level: 3, count: 1 },
+        ]
+    );
+
+    let err = ExecHandler::execute_recount_levels(deps.as_mut(), mock_info(OWNER, &[]), None)
+        .unwrap_err();
+    assert_eq!(err, ContractError::NoLevelRecount {});
+}
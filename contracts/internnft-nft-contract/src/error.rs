@@ -20,6 +20,110 @@ pub enum ContractError {
     #[error("Per-wallet token allotment exceeded")]
     WalletLimit {},
 
+    #[error("Token supply exhausted")]
+    SupplyExhausted {},
+
+    #[error("Minting is closed")]
+    MintClosed {},
+
+    #[error("Mint quantity must be greater than zero")]
+    InvalidQuantity {},
+
+    #[error("Allowlist minting is not enabled")]
+    AllowlistDisabled {},
+
+    #[error("Address is not on the allowlist")]
+    NotAllowlisted {},
+
+    #[error("Allowlist allotment exceeded")]
+    AllowlistLimit {},
+
+    #[error("Collection is already revealed")]
+    AlreadyRevealed {},
+
+    #[error("Reveal has already been requested")]
+    RevealRequested {},
+
+    #[error("Reveal has not been requested")]
+    NoRevealRequested {},
+
+    #[error("Token supply cannot change once a provenance hash is committed")]
+    SupplyFrozen {},
+
+    #[error("Voucher minting is not enabled")]
+    VouchersDisabled {},
+
+    #[error("Invalid voucher signature")]
+    InvalidSignature {},
+
+    #[error("Voucher was issued for another contract")]
+    VoucherWrongContract {},
+
+    #[error("Voucher has expired")]
+    VoucherExpired {},
+
+    #[error("Voucher has already been redeemed")]
+    VoucherUsed {},
+
+    #[error(
+        "Names must be 1 to 32 letters, digits, spaces or -_'. without leading or trailing spaces"
+    )]
+    InvalidName {},
+
+    #[error("Descriptions must be at most 256 characters, without control characters")]
+    InvalidDescription {},
+
+    #[error("Name is already taken")]
+    NameTaken {},
+
+    #[error("Profile update has to set a name or a description")]
+    EmptyProfile {},
+
+    #[error("No payees are configured")]
+    NoPayees {},
+
+    #[error("Revenue is split between the payees, use Distribute")]
+    PayeesConfigured {},
+
+    #[error("Contract is paused for {operation}")]
+    Paused { operation: String },
+
+    #[error("No ownership transfer has been proposed")]
+    NoPendingOwner {},
+
+    #[error("Funds are reserved for burn redemptions")]
+    RedemptionReserved {},
+
+    #[error("No level recount is in progress")]
+    NoLevelRecount {},
+
+    #[error("Intern has no starting traits left to roll")]
+    NoPendingRoll {},
+
+    #[error("Ownership proposal has expired")]
+    OwnershipProposalExpired {},
+
+    #[error("Ownership proposal cannot be already expired")]
+    OwnershipProposalAlreadyExpired {},
+
+    #[error("Not permitted to update {name}")]
+    TraitNotPermitted { name: String },
+
+    #[error("Adjustment would take {name} below zero or out of range")]
+    TraitOutOfRange { name: String },
+
+    #[error("Traits don't match the expected values")]
+    TraitMismatch {},
+
+    #[error("Gold token is not configured")]
+    GoldDisabled {},
+
+    #[error("Intern doesn't hold enough gold")]
+    InsufficientGold {},
+
+    #[error("Gold amount must be a positive multiple of the conversion rate")]
+    InvalidGoldAmount {},
+
     #[error("Move target out-of-bounds or already occupied")]
     InvalidMoveTarget {},
 
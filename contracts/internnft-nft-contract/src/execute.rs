@@ -1,20 +1,67 @@
+use std::convert::TryFrom;
+
 use cosmwasm_std::{
-    Attribute, BankMsg, Binary, Coin, DepsMut, Empty, Env, MessageInfo, Order, Response, StdError,
-    StdResult, Storage,
+    from_binary, to_binary, to_vec, Addr, Attribute, BankMsg, Binary, Coin, Decimal, Deps, DepsMut,
+    Empty, Env, Event, MessageInfo, Order, QueryRequest, Response, StdError, StdResult, Storage,
+    Uint128, WasmMsg, WasmQuery,
 };
-use cw721::{ContractInfoResponse, Cw721ReceiveMsg};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::{ContractInfoResponse, Cw721ReceiveMsg, Expiration};
 use cw721_base::{msg::ExecuteMsg as Cw721ExecuteMsg, Cw721Contract};
+use cw_storage_plus::{Bound, U64Key};
 use internnft::nft::{
-    full_token_id, numeric_token_id, Config, ExecuteMsg, InstantiateMsg, InternExtension,
-    MigrateMsg,
+    full_token_id, numeric_token_id, Config, ConfigUpdate, ExecuteMsg, GoldHookMsg, InstantiateMsg,
+    InternExtension, InternTokenInfo, InternTrait, MigrateMsg, MintPhase, MintVoucher, PauseState,
+    Payee, PendingOwner, PendingRoll, RarityTier, Royalty, TraitChange, TraitDeltas,
 };
+use internnft::staking::ContractQuery::GetRandomness;
+use internnft::staking::GetRandomResponse;
+use rsa::pkcs1::FromRsaPublicKey;
+use rsa::{Hash, PaddingScheme, PublicKey, RsaPublicKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
 
 use crate::error::ContractError;
-use crate::state::{tokens, CONFIG, OWNER};
+use crate::state::{
+    count_level, minted_count, pending_payments, public_supply, tokens, update_collection_totals,
+    AIRDROPPED, ALLOWLIST_MINTS, BURNED, BURN_COUNT, COLLECTION_TOTALS, CONFIG, LEVEL_RECOUNT,
+    OWNER, PAUSED, PAYEES, PENDING_OWNER, PENDING_ROLLS, PHASE_MINTS, PHASE_SUPPLY,
+    PROVENANCE_HASH, RARITIES, REDEMPTION_POOL, RELEASED, REVEAL_OFFSET, REVEAL_ROUND,
+    TOKEN_ROYALTIES, TOTAL_RELEASED, TRAIT_HISTORY, TRAIT_HISTORY_COUNT, UPDATERS, USED_NONCES,
+};
 
 const INTERN: &str = "intern";
+/// The stamina every intern starts out with, unless rarity tiers are configured.
+const STARTING_STAMINA: u64 = 100;
+
+// drand genesis time and round period, used to find the current terrand round
+const GENESIS_TIME: u64 = 1595431050;
+const PERIOD: u64 = 30;
+// rounds between the current terrand round and the one randomness is committed to, so the
+// committed round can't have been published yet
+const COMMIT_ROUNDS_AHEAD: u64 = 2;
+
+// number of interns counted per RecountLevels call
+const DEFAULT_RECOUNT_LIMIT: u32 = 100;
+const MAX_RECOUNT_LIMIT: u32 = 500;
+
+// how long a proposed owner has to accept, unless the proposal says otherwise
+const OWNERSHIP_PROPOSAL_DURATION: u64 = 7 * 24 * 60 * 60;
 
 pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+    if let Some(provenance_hash) = &msg.provenance_hash {
+        let valid =
+            provenance_hash.len() == 64 && provenance_hash.chars().all(|c| c.is_ascii_hexdigit());
+        if !valid {
+            return Err(StdError::generic_err(
+                "provenance hash must be a hex encoded sha256 hash",
+            ));
+        }
+        PROVENANCE_HASH.save(deps.storage, &provenance_hash.to_lowercase())?;
+    }
+
+    validate_config(deps.as_ref(), &msg.config)?;
+
     let cw721_contract = Cw721Contract::<InternExtension, Empty>::default();
 
     let contract_info = ContractInfoResponse {
@@ -32,48 +79,1018 @@ pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> Std
 }
 
 pub fn execute_mint(
-    _deps: DepsMut,
-    _env: Env,
+    deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    quantity: Option<u32>,
+    proof: Option<Vec<Binary>>,
 ) -> Result<Response, ContractError> {
-    //TODO: Write minting contract.
-    Ok(Response::new()
+    let config = CONFIG.load(deps.storage)?;
+    let owner = OWNER.may_load(deps.storage)?;
+    let quantity = quantity.unwrap_or(1);
+    if quantity == 0 {
+        return Err(ContractError::InvalidQuantity {});
+    }
+
+    // with a schedule configured, minting is only open during one of its phases
+    let phase = match config.mint_phases.is_empty() {
+        true => None,
+        false => match config.active_mint_phase(env.block.time) {
+            Some(phase) => Some(phase.clone()),
+            None => return Err(ContractError::MintClosed {}),
+        },
+    };
+
+    // the mint fee doesn't apply to the contract owner
+    if owner.as_deref() != Some(info.sender.as_str()) {
+        let price = match &phase {
+            Some(phase) => phase.price.clone(),
+            None => config.mint_fee.clone(),
+        };
+        let fee = total_price(price, quantity);
+        check_sufficient_funds(info.funds.clone(), fee.clone())?;
+        reserve_redemption(deps.storage, &config, &fee)?;
+    }
+
+    // allowlist mints count against the allowlist allotment instead of the wallet limit
+    let allowlist = proof.is_some();
+    match proof {
+        Some(proof) => {
+            check_allowlist_proof(&config, &info.sender, &proof)?;
+            ALLOWLIST_MINTS.update(deps.storage, &info.sender, |minted| {
+                let minted = minted.unwrap_or_default() + quantity;
+                if minted > config.allowlist_limit {
+                    Err(ContractError::AllowlistLimit {})
+                } else {
+                    Ok(minted)
+                }
+            })?;
+        }
+        None if matches!(&phase, Some(phase) if phase.allowlist) => {
+            return Err(ContractError::NotAllowlisted {});
+        }
+        None => check_wallet_limit(
+            deps.storage,
+            info.sender.clone(),
+            config.wallet_limit,
+            quantity,
+        )?,
+    }
+
+    let mut response = Response::new();
+    if let Some(phase) = phase {
+        PHASE_MINTS.update(deps.storage, (&phase.name, &info.sender), |minted| {
+            let minted = minted.unwrap_or_default() + quantity;
+            if minted > phase.wallet_limit {
+                Err(ContractError::WalletLimit {})
+            } else {
+                Ok(minted)
+            }
+        })?;
+        PHASE_SUPPLY.update(deps.storage, &phase.name, |minted| {
+            let minted = minted.unwrap_or_default() + quantity as u64;
+            if minted > phase.supply.unwrap_or(u64::MAX) {
+                Err(ContractError::SupplyExhausted {})
+            } else {
+                Ok(minted)
+            }
+        })?;
+        response = response.add_attribute("phase", phase.name);
+    }
+
+    let supply = public_supply(deps.storage, &config)?;
+    let roll_round = roll_round(&env, &config);
+    let minted = mint_tokens(
+        deps.storage,
+        &config,
+        &info.sender,
+        quantity,
+        supply,
+        roll_round,
+    )?;
+
+    Ok(response
         .add_attribute("action", "mint")
-        .add_attribute("minter", info.sender))
+        .add_attribute("minter", info.sender)
+        .add_attribute("quantity", quantity.to_string())
+        .add_attributes(minted_attributes(&minted, roll_round))
+        .add_attribute("allowlist", allowlist.to_string()))
 }
 
-pub fn execute_update_traits(
+pub fn execute_mint_with_voucher(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
+    voucher: MintVoucher,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let public_key = match &config.voucher_public_key {
+        Some(public_key) => RsaPublicKey::from_pkcs1_der(public_key.as_slice())
+            .map_err(|_| StdError::generic_err("invalid voucher public key"))?,
+        None => return Err(ContractError::VouchersDisabled {}),
+    };
+
+    let digest = Sha256::digest(&to_vec(&voucher)?);
+    public_key
+        .verify(
+            PaddingScheme::new_pkcs1v15_sign(Some(Hash::SHA2_256)),
+            &digest,
+            signature.as_slice(),
+        )
+        .map_err(|_| ContractError::InvalidSignature {})?;
+
+    if voucher.contract != env.contract.address {
+        return Err(ContractError::VoucherWrongContract {});
+    }
+    if env.block.time.seconds() > voucher.expires {
+        return Err(ContractError::VoucherExpired {});
+    }
+    if voucher.quantity == 0 {
+        return Err(ContractError::InvalidQuantity {});
+    }
+
+    let fee = total_price(voucher.price.clone(), voucher.quantity);
+    check_sufficient_funds(info.funds.clone(), fee.clone())?;
+    reserve_redemption(deps.storage, &config, &fee)?;
+
+    USED_NONCES.update(
+        deps.storage,
+        U64Key::new(voucher.nonce),
+        |used| match used {
+            Some(_) => Err(ContractError::VoucherUsed {}),
+            None => Ok(true),
+        },
+    )?;
+
+    let recipient = deps.api.addr_validate(&voucher.recipient)?;
+    check_wallet_limit(
+        deps.storage,
+        recipient.clone(),
+        config.wallet_limit,
+        voucher.quantity,
+    )?;
+    let supply = public_supply(deps.storage, &config)?;
+    let roll_round = roll_round(&env, &config);
+    let minted = mint_tokens(
+        deps.storage,
+        &config,
+        &recipient,
+        voucher.quantity,
+        supply,
+        roll_round,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "mint_with_voucher")
+        .add_attribute("minter", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("nonce", voucher.nonce.to_string())
+        .add_attributes(minted_attributes(&minted, roll_round)))
+}
+
+/// An intern created by `mint_tokens` or rolled by `execute_roll_traits`, along with its
+/// starting traits.
+struct MintedIntern {
     token_id: String,
-    exp: u64,
-    gold: u64,
+    rarity: Option<String>,
     stamina: u64,
+}
+
+/// Creates the next `quantity` sequential interns for the given owner. `supply` is the number
+/// of interns still available to this mint. If `roll_round` is set, the interns start out
+/// without stamina until their traits are rolled from that terrand round.
+fn mint_tokens(
+    storage: &mut dyn Storage,
+    config: &Config,
+    owner: &Addr,
+    quantity: u32,
+    supply: u64,
+    roll_round: Option<u64>,
+) -> Result<Vec<MintedIntern>, ContractError> {
+    let cw721_contract = Cw721Contract::<InternExtension, Empty>::default();
+
+    check_not_paused(storage, "minting", |paused| paused.minting)?;
+    if quantity as u64 > supply {
+        return Err(ContractError::SupplyExhausted {});
+    }
+
+    let mut minted = vec![];
+    for _ in 0..quantity {
+        // token ids are allocated sequentially, starting from 1 and skipping over burned ids
+        let burned = BURN_COUNT.may_load(storage)?.unwrap_or_default();
+        let token_id = (cw721_contract.increment_tokens(storage)? + burned).to_string();
+        let stamina = match roll_round {
+            Some(_) => 0,
+            None => STARTING_STAMINA,
+        };
+        let token = InternTokenInfo {
+            owner: owner.clone(),
+            approvals: vec![],
+            name: full_token_id(token_id.clone())?,
+            description: "".to_string(),
+            image: None,
+            extension: InternExtension {
+                experience: 0,
+                gold: 0,
+                stamina,
+            },
+        };
+        tokens().update(storage, &token.name.clone(), |old| match old {
+            Some(_) => Err(ContractError::Claimed {}),
+            None => Ok(token.clone()),
+        })?;
+        // the tiers are rolled as they are now, so they can't be changed after the round is out
+        if let Some(round) = roll_round {
+            let roll = PendingRoll {
+                round,
+                tiers: config.rarity_tiers.clone(),
+            };
+            PENDING_ROLLS.save(storage, &token.name, &roll)?;
+        }
+        update_collection_totals(
+            storage,
+            &config.level_curve,
+            &token.name,
+            None,
+            Some(&token),
+        )?;
+        minted.push(MintedIntern {
+            token_id,
+            rarity: None,
+            stamina,
+        });
+    }
+
+    Ok(minted)
+}
+
+/// Lists the minted token ids and their starting traits, so indexers can pick them up. Interns
+/// still waiting on their trait rolls only report the terrand round they'll be rolled from.
+fn minted_attributes(minted: &[MintedIntern], roll_round: Option<u64>) -> Vec<Attribute> {
+    let join = |values: Vec<String>| values.join(",");
+    let mut attributes = vec![Attribute::new(
+        "token_ids",
+        join(
+            minted
+                .iter()
+                .map(|intern| intern.token_id.clone())
+                .collect(),
+        ),
+    )];
+    if let Some(round) = roll_round {
+        attributes.push(Attribute::new("roll_round", round.to_string()));
+        return attributes;
+    }
+    attributes.push(Attribute::new(
+        "stamina",
+        join(
+            minted
+                .iter()
+                .map(|intern| intern.stamina.to_string())
+                .collect(),
+        ),
+    ));
+    if minted.iter().any(|intern| intern.rarity.is_some()) {
+        attributes.push(Attribute::new(
+            "rarity",
+            join(
+                minted
+                    .iter()
+                    .map(|intern| intern.rarity.clone().unwrap_or_default())
+                    .collect(),
+            ),
+        ));
+    }
+    attributes
+}
+
+/// Returns the terrand round new interns roll their starting traits from, or `None` if trait
+/// rolls aren't configured.
+fn roll_round(env: &Env, config: &Config) -> Option<u64> {
+    match &config.terrand_addr {
+        Some(_) if !config.rarity_tiers.is_empty() => Some(commit_round(env)),
+        _ => None,
+    }
+}
+
+/// Returns the earliest terrand round that can't have been published by the time of the
+/// current block, so nobody can know its randomness when committing to it.
+fn commit_round(env: &Env) -> u64 {
+    env.block.time.seconds().saturating_sub(GENESIS_TIME) / PERIOD + COMMIT_ROUNDS_AHEAD
+}
+
+/// Fetches the terrand randomness of the given round, failing if it isn't published yet.
+fn query_randomness(deps: Deps, terrand_addr: &str, round: u64) -> StdResult<Binary> {
+    let res: GetRandomResponse = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: terrand_addr.to_string(),
+        msg: to_binary(&GetRandomness { round })?,
+    }))?;
+    Ok(res.randomness)
+}
+
+/// Picks a weighted rarity tier and a starting stamina within the tier's range. Each token
+/// rolls from its committed round's randomness hashed together with its token id.
+fn roll_starting_traits<'a>(
+    tiers: &'a [RarityTier],
+    randomness: &Binary,
+    token_id: &str,
+) -> (Option<&'a RarityTier>, u64) {
+    let seed = Sha256::new()
+        .chain(randomness.as_slice())
+        .chain(token_id.as_bytes())
+        .finalize();
+    let mut tier_roll = [0u8; 8];
+    tier_roll.copy_from_slice(&seed[0..8]);
+    let mut stamina_roll = [0u8; 8];
+    stamina_roll.copy_from_slice(&seed[8..16]);
+
+    let total_weight: u64 = tiers.iter().map(|tier| tier.weight as u64).sum();
+    if total_weight == 0 {
+        return (None, STARTING_STAMINA);
+    }
+
+    let mut roll = u64::from_be_bytes(tier_roll) % total_weight;
+    for tier in tiers {
+        if roll < tier.weight as u64 {
+            let range = tier
+                .max_stamina
+                .saturating_sub(tier.min_stamina)
+                .saturating_add(1);
+            let stamina = tier.min_stamina + u64::from_be_bytes(stamina_roll) % range;
+            return (Some(tier), stamina);
+        }
+        roll -= tier.weight as u64;
+    }
+    (None, STARTING_STAMINA)
+}
+
+/// Sets aside the burn redemption share of a mint fee paid in the mint fee denom, keeping it out
+/// of the payees' split and the owner's withdrawals.
+fn reserve_redemption(storage: &mut dyn Storage, config: &Config, fee: &Coin) -> StdResult<()> {
+    if let Some(rate) = config.burn_redemption {
+        if fee.denom == config.mint_fee.denom {
+            let pool = REDEMPTION_POOL.may_load(storage)?.unwrap_or_default();
+            REDEMPTION_POOL.save(storage, &(pool + fee.amount * rate))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the price of minting `quantity` tokens at the given unit price.
+fn total_price(price: Coin, quantity: u32) -> Coin {
+    Coin {
+        denom: price.denom,
+        amount: price.amount * Uint128::from(quantity),
+    }
+}
+
+pub fn execute_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<(String, u32)>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    // airdrops can use the whole remaining supply, including the reserved part
+    let mut supply = config
+        .token_supply
+        .saturating_sub(minted_count(deps.storage)?);
+
+    let roll_round = roll_round(&env, &config);
+    let mut minted = vec![];
+    let mut total: u64 = 0;
+    for (recipient, quantity) in recipients {
+        if quantity == 0 {
+            return Err(ContractError::InvalidQuantity {});
+        }
+        let recipient = deps.api.addr_validate(&recipient)?;
+        minted.extend(mint_tokens(
+            deps.storage,
+            &config,
+            &recipient,
+            quantity,
+            supply,
+            roll_round,
+        )?);
+        supply -= quantity as u64;
+        total += quantity as u64;
+    }
+    let airdropped = AIRDROPPED.may_load(deps.storage)?.unwrap_or_default();
+    AIRDROPPED.save(deps.storage, &(airdropped + total))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "airdrop")
+        .add_attribute("quantity", total.to_string())
+        .add_attributes(minted_attributes(&minted, roll_round)))
+}
+
+pub fn execute_roll_traits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_ids: Vec<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let staking_contract = config.staking_contract;
+    let terrand_addr = config
+        .terrand_addr
+        .ok_or_else(|| StdError::generic_err("terrand address is not configured"))?;
+
+    let mut rolled = vec![];
+    for numeric_id in token_ids {
+        let token_id = full_token_id(numeric_id.clone())?;
+        let roll = PENDING_ROLLS
+            .may_load(deps.storage, &token_id)?
+            .ok_or(ContractError::NoPendingRoll {})?;
+        let randomness = query_randomness(deps.as_ref(), &terrand_addr, roll.round)?;
+        let (rarity, stamina) = roll_starting_traits(&roll.tiers, &randomness, &numeric_id);
+
+        let token = tokens().load(deps.storage, &token_id)?;
+        let mut new_token = token.clone();
+        new_token.extension.stamina = token.extension.stamina.saturating_add(stamina);
+        tokens().replace(deps.storage, &token_id, Some(&new_token), Some(&token))?;
+        update_collection_totals(
+            deps.storage,
+            &config.level_curve,
+            &token_id,
+            Some(&token),
+            Some(&new_token),
+        )?;
+        let change = TraitUpdate {
+            sender: &info.sender,
+            token_id: &token_id,
+            action: "roll_traits",
+            reason: Some("starting traits".to_string()),
+        };
+        record_trait_change(
+            deps.storage,
+            &env,
+            change,
+            token.extension,
+            new_token.extension,
+        )?;
+        PENDING_ROLLS.remove(deps.storage, &token_id);
+        if let Some(tier) = rarity {
+            RARITIES.save(deps.storage, &token_id, &tier.name)?;
+        }
+
+        rolled.push(MintedIntern {
+            token_id: numeric_id,
+            rarity: rarity.map(|tier| tier.name.clone()),
+            stamina,
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "roll_traits")
+        .add_attributes(minted_attributes(&rolled, None)))
+}
+
+pub fn execute_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if PROVENANCE_HASH.may_load(deps.storage)?.is_none()
+        || REVEAL_OFFSET.may_load(deps.storage)?.is_some()
+    {
+        return Err(ContractError::AlreadyRevealed {});
+    }
+    if REVEAL_ROUND.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::RevealRequested {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if config.terrand_addr.is_none() {
+        return Err(StdError::generic_err("terrand address is not configured").into());
+    }
+
+    // the offset is drawn from a round that isn't published yet, so the owner can't pick it
+    let round = commit_round(&env);
+    REVEAL_ROUND.save(deps.storage, &round)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "reveal")
+        .add_attribute("reveal_round", round.to_string()))
+}
+
+pub fn execute_finalize_reveal(deps: DepsMut) -> Result<Response, ContractError> {
+    if REVEAL_OFFSET.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::AlreadyRevealed {});
+    }
+    let round = REVEAL_ROUND
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoRevealRequested {})?;
+    let config = CONFIG.load(deps.storage)?;
+    let terrand_addr = config
+        .terrand_addr
+        .ok_or_else(|| StdError::generic_err("terrand address is not configured"))?;
+    let randomness = query_randomness(deps.as_ref(), &terrand_addr, round)?;
+
+    let mut roll = [0u8; 8];
+    roll.copy_from_slice(&Sha256::digest(randomness.as_slice())[0..8]);
+    let offset = u64::from_be_bytes(roll) % config.token_supply.max(1);
+    REVEAL_OFFSET.save(deps.storage, &offset)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "finalize_reveal")
+        .add_attribute("offset", offset.to_string()))
+}
+
+pub fn execute_burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let cw721_contract = Cw721Contract::<InternExtension, Empty>::default();
+    let config = CONFIG.load(deps.storage)?;
+    let numeric_id = token_id
+        .parse::<u64>()
+        .map_err(|_| StdError::generic_err("expected numeric token identifier"))?;
+    let token_id = full_token_id(token_id)?;
     let token = tokens().load(deps.storage, &token_id)?;
+    check_can_send(deps.as_ref(), &env, &info, &token)?;
+
+    // the burner gets their intern's share of the redemption pool, split evenly between all
+    // held interns
+    let held = cw721_contract.token_count(deps.storage)?;
+    let pool = REDEMPTION_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let redemption = pool.multiply_ratio(1u128, held);
+    REDEMPTION_POOL.save(deps.storage, &(pool - redemption))?;
+
+    tokens().remove(deps.storage, &token_id)?;
+    update_collection_totals(
+        deps.storage,
+        &config.level_curve,
+        &token_id,
+        Some(&token),
+        None,
+    )?;
+    TOKEN_ROYALTIES.remove(deps.storage, &token_id);
+    PENDING_ROLLS.remove(deps.storage, &token_id);
+    RARITIES.remove(deps.storage, &token_id);
+    cw721_contract.token_count.save(deps.storage, &(held - 1))?;
+    let burned = BURN_COUNT.may_load(deps.storage)?.unwrap_or_default();
+    BURN_COUNT.save(deps.storage, &(burned + 1))?;
+    BURNED.save(deps.storage, U64Key::new(numeric_id), &info.sender)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("sender", info.sender.to_string())
+        .add_attribute("token_id", numeric_token_id(token_id)?)
+        .add_attribute("redemption", redemption.to_string());
+    if !redemption.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: config.mint_fee.denom,
+                amount: redemption,
+            }],
+        });
+    }
+    Ok(response)
+}
+
+/// Mirrors the cw721-base check, which isn't public: the owner, an approved spender or an
+/// operator of the owner can send (or burn) the token.
+fn check_can_send(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    token: &InternTokenInfo,
+) -> Result<(), ContractError> {
+    if token.owner == info.sender {
+        return Ok(());
+    }
+    if token
+        .approvals
+        .iter()
+        .any(|approval| approval.spender == info.sender && !approval.is_expired(&env.block))
+    {
+        return Ok(());
+    }
+    let cw721_contract = Cw721Contract::<InternExtension, Empty>::default();
+    match cw721_contract
+        .operators
+        .may_load(deps.storage, (&token.owner, &info.sender))?
+    {
+        Some(expires) if !expires.is_expired(&env.block) => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
 
-    //right now, only the staking contract can update the traits
-    if info.sender != staking_contract {
+pub fn execute_withdraw_gold(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    amount: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let gold_token = config.gold_token.ok_or(ContractError::GoldDisabled {})?;
+    check_not_paused(deps.storage, "trait updates", |paused| paused.trait_updates)?;
+    if amount == 0 {
+        return Err(ContractError::InvalidGoldAmount {});
+    }
+    let token_id = full_token_id(token_id)?;
+    let token = tokens().load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
         return Err(ContractError::Unauthorized {});
     }
 
     let mut new_token = token.clone();
-    new_token.extension.experience = exp;
-    new_token.extension.gold = gold;
-    new_token.extension.stamina = stamina;
+    new_token.extension.gold = token
+        .extension
+        .gold
+        .checked_sub(amount)
+        .ok_or(ContractError::InsufficientGold {})?;
     tokens().replace(deps.storage, &token_id, Some(&new_token), Some(&token))?;
+    update_collection_totals(
+        deps.storage,
+        &config.level_curve,
+        &token_id,
+        Some(&token),
+        Some(&new_token),
+    )?;
+    let change = TraitUpdate {
+        sender: &info.sender,
+        token_id: &token_id,
+        action: "withdraw_gold",
+        reason: Some("withdraw gold".to_string()),
+    };
+    record_trait_change(
+        deps.storage,
+        &env,
+        change,
+        token.extension,
+        new_token.extension,
+    )?;
+
+    let minted = gold_token
+        .rate
+        .checked_mul(Uint128::from(amount))
+        .map_err(StdError::from)?;
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: gold_token.address,
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: info.sender.to_string(),
+                amount: minted,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "withdraw_gold")
+        .add_attribute("token_id", numeric_token_id(token_id)?)
+        .add_attribute("gold", amount.to_string())
+        .add_attribute("minted", minted.to_string()))
+}
+
+pub fn execute_set_profile(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<Response, ContractError> {
+    if name.is_none() && description.is_none() {
+        return Err(ContractError::EmptyProfile {});
+    }
+    // the fee is paid in gold, so this is a trait update like any other gold movement
+    check_not_paused(deps.storage, "trait updates", |paused| paused.trait_updates)?;
+    let config = CONFIG.load(deps.storage)?;
+    let token_id = full_token_id(token_id)?;
+    let token = tokens().load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut new_token = token.clone();
+    if let Some(name) = name {
+        validate_name(&name)?;
+        let holder = tokens()
+            .idx
+            .name
+            .item(deps.storage, name.to_lowercase().into_bytes())?;
+        if let Some((holder, _)) = holder {
+            if holder != token_id.as_bytes() {
+                return Err(ContractError::NameTaken {});
+            }
+        }
+        new_token.name = name;
+    }
+    if let Some(description) = description {
+        if description.chars().count() > 256 || description.chars().any(char::is_control) {
+            return Err(ContractError::InvalidDescription {});
+        }
+        new_token.description = description;
+    }
+    new_token.extension.gold = token
+        .extension
+        .gold
+        .checked_sub(config.profile_fee)
+        .ok_or(ContractError::InsufficientGold {})?;
+    tokens().replace(deps.storage, &token_id, Some(&new_token), Some(&token))?;
+    update_collection_totals(
+        deps.storage,
+        &config.level_curve,
+        &token_id,
+        Some(&token),
+        Some(&new_token),
+    )?;
+    if config.profile_fee > 0 {
+        let change = TraitUpdate {
+            sender: &info.sender,
+            token_id: &token_id,
+            action: "set_profile",
+            reason: Some("profile fee".to_string()),
+        };
+        record_trait_change(
+            deps.storage,
+            &env,
+            change,
+            token.extension,
+            new_token.extension,
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_profile")
+        .add_attribute("token_id", numeric_token_id(token_id)?)
+        .add_attribute("name", new_token.name)
+        .add_attribute("gold", config.profile_fee.to_string()))
+}
+
+// default names ("intern #1") contain a '#', so custom names can never clash with them
+fn validate_name(name: &str) -> Result<(), ContractError> {
+    let allowed = |c: char| c.is_ascii_alphanumeric() || " -_'.".contains(c);
+    if name.is_empty() || name.len() > 32 || !name.chars().all(allowed) || name.trim() != name {
+        return Err(ContractError::InvalidName {});
+    }
+    Ok(())
+}
+
+/// Handles gold tokens sent to the contract, burning them into gold held by an intern.
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let gold_token = config.gold_token.ok_or(ContractError::GoldDisabled {})?;
+    if info.sender != gold_token.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    check_not_paused(deps.storage, "trait updates", |paused| paused.trait_updates)?;
+
+    let GoldHookMsg::Deposit { token_id } = from_binary(&msg.msg)?;
+    let amount = msg.amount.u128();
+    let rate = gold_token.rate.u128();
+    if amount == 0 || amount % rate != 0 {
+        return Err(ContractError::InvalidGoldAmount {});
+    }
+    let gold = u64::try_from(amount / rate).map_err(|_| ContractError::InvalidGoldAmount {})?;
+
+    let token_id = full_token_id(token_id)?;
+    let token = tokens().load(deps.storage, &token_id)?;
+    let mut new_token = token.clone();
+    new_token.extension.gold = token
+        .extension
+        .gold
+        .checked_add(gold)
+        .ok_or(ContractError::InvalidGoldAmount {})?;
+    tokens().replace(deps.storage, &token_id, Some(&new_token), Some(&token))?;
+    update_collection_totals(
+        deps.storage,
+        &config.level_curve,
+        &token_id,
+        Some(&token),
+        Some(&new_token),
+    )?;
+    let change = TraitUpdate {
+        sender: &deps.api.addr_validate(&msg.sender)?,
+        token_id: &token_id,
+        action: "deposit_gold",
+        reason: Some("deposit gold".to_string()),
+    };
+    record_trait_change(
+        deps.storage,
+        &env,
+        change,
+        token.extension,
+        new_token.extension,
+    )?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: gold_token.address,
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount: msg.amount })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "deposit_gold")
+        .add_attribute("sender", msg.sender)
+        .add_attribute("token_id", numeric_token_id(token_id)?)
+        .add_attribute("gold", gold.to_string()))
+}
+
+pub fn execute_update_traits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    extension: InternExtension,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let token_id = full_token_id(token_id)?;
+    let change = TraitUpdate {
+        sender: &info.sender,
+        token_id: &token_id,
+        action: "update_traits",
+        reason,
+    };
+    apply_traits(deps, &env, change, |_| Ok(extension))
+}
+
+pub fn execute_adjust_traits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    deltas: TraitDeltas,
+    expected: Option<InternExtension>,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    let token_id = full_token_id(token_id)?;
+    let change = TraitUpdate {
+        sender: &info.sender,
+        token_id: &token_id,
+        action: "adjust_traits",
+        reason,
+    };
+    apply_traits(deps, &env, change, |current| {
+        if let Some(expected) = expected {
+            if *current != expected {
+                return Err(ContractError::TraitMismatch {});
+            }
+        }
+        Ok(InternExtension {
+            experience: adjust_trait(current, &deltas, InternTrait::Experience)?,
+            gold: adjust_trait(current, &deltas, InternTrait::Gold)?,
+            stamina: adjust_trait(current, &deltas, InternTrait::Stamina)?,
+        })
+    })
+}
+
+fn adjust_trait(
+    current: &InternExtension,
+    deltas: &TraitDeltas,
+    intern_trait: InternTrait,
+) -> Result<u64, ContractError> {
+    let value = current.get(intern_trait) as i128 + deltas.get(intern_trait) as i128;
+    u64::try_from(value).map_err(|_| ContractError::TraitOutOfRange {
+        name: intern_trait.name().to_string(),
+    })
+}
+
+/// Who is updating which intern's traits, and why.
+struct TraitUpdate<'a> {
+    sender: &'a Addr,
+    token_id: &'a str,
+    action: &'a str,
+    reason: Option<String>,
+}
+
+/// Updates the traits of an intern on behalf of a game contract, which has to be allowed to
+/// change every trait that differs. All trait changes made by game contracts go through here.
+fn apply_traits<F>(
+    deps: DepsMut,
+    env: &Env,
+    change: TraitUpdate,
+    update: F,
+) -> Result<Response, ContractError>
+where
+    F: FnOnce(&InternExtension) -> Result<InternExtension, ContractError>,
+{
+    check_not_paused(deps.storage, "trait updates", |paused| paused.trait_updates)?;
+    let config = CONFIG.load(deps.storage)?;
+    let token_id = change.token_id;
+    let token = tokens().load(deps.storage, token_id)?;
+
+    let permitted = match change.sender.as_str() == config.staking_contract {
+        true => InternTrait::ALL.to_vec(),
+        false => UPDATERS
+            .may_load(deps.storage, change.sender)?
+            .ok_or(ContractError::Unauthorized {})?,
+    };
+    let extension = update(&token.extension)?;
+    if let Some(denied) = token
+        .extension
+        .changed_traits(&extension)
+        .into_iter()
+        .find(|intern_trait| !permitted.contains(intern_trait))
+    {
+        return Err(ContractError::TraitNotPermitted {
+            name: denied.name().to_string(),
+        });
+    }
+
+    let mut new_token = token.clone();
+    new_token.extension = extension;
+    tokens().replace(deps.storage, token_id, Some(&new_token), Some(&token))?;
+    update_collection_totals(
+        deps.storage,
+        &config.level_curve,
+        token_id,
+        Some(&token),
+        Some(&new_token),
+    )?;
+
+    let action = change.action;
+    record_trait_change(deps.storage, env, change, token.extension, extension)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", action)
+        .add_attribute("experience", extension.experience.to_string())
+        .add_attribute("gold", extension.gold.to_string())
+        .add_attribute("stamina", extension.stamina.to_string());
+
+    let level = config.level_curve.level(extension.experience);
+    if level > config.level_curve.level(token.extension.experience) {
+        response = response.add_event(
+            Event::new("level_up")
+                .add_attribute("token_id", numeric_token_id(token_id.to_string())?)
+                .add_attribute("level", level.to_string()),
+        );
+    }
+    Ok(response)
+}
+
+/// Records a change to an intern's traits in its trait history. Every trait change goes
+/// through here, whether it's made by a game contract or by moving gold in or out of an intern.
+fn record_trait_change(
+    storage: &mut dyn Storage,
+    env: &Env,
+    change: TraitUpdate,
+    old: InternExtension,
+    new: InternExtension,
+) -> StdResult<()> {
+    let id = TRAIT_HISTORY_COUNT
+        .may_load(storage, change.token_id)?
+        .unwrap_or_default()
+        + 1;
+    TRAIT_HISTORY_COUNT.save(storage, change.token_id, &id)?;
+    TRAIT_HISTORY.save(
+        storage,
+        (change.token_id, U64Key::new(id)),
+        &TraitChange {
+            id,
+            updater: change.sender.to_string(),
+            block_height: env.block.height,
+            old,
+            new,
+            reason: change.reason,
+        },
+    )
+}
+
+pub fn execute_add_updater(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    traits: Vec<InternTrait>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    UPDATERS.save(deps.storage, &address, &traits)?;
+
+    let names: Vec<&str> = traits
+        .iter()
+        .map(|intern_trait| intern_trait.name())
+        .collect();
+    Ok(Response::new()
+        .add_attribute("action", "add_updater")
+        .add_attribute("updater", address)
+        .add_attribute("traits", names.join(",")))
+}
+
+pub fn execute_remove_updater(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    UPDATERS.remove(deps.storage, &address);
 
     Ok(Response::new()
-        .add_attribute("action", "update_traits")
-        .add_attribute("experience", exp.to_string())
-        .add_attribute("gold", gold.to_string())
-        .add_attribute("stamina", stamina.to_string()))
+        .add_attribute("action", "remove_updater")
+        .add_attribute("updater", address))
 }
 
-#[allow(dead_code)]
 fn check_sufficient_funds(funds: Vec<Coin>, required: Coin) -> Result<(), ContractError> {
     if required.amount.u128() == 0 {
         return Ok(());
@@ -92,11 +1109,40 @@ fn check_sufficient_funds(funds: Vec<Coin>, required: Coin) -> Result<(), Contra
     }
 }
 
-#[allow(dead_code)]
+/// Verifies a Merkle proof that the address is part of the configured allowlist.
+/// Leaves are the sha256 digest of the address, and pairs are hashed in sorted order.
+fn check_allowlist_proof(
+    config: &Config,
+    address: &Addr,
+    proof: &[Binary],
+) -> Result<(), ContractError> {
+    let root = match &config.allowlist_root {
+        Some(root) => root,
+        None => return Err(ContractError::AllowlistDisabled {}),
+    };
+
+    let leaf = Sha256::digest(address.as_bytes()).to_vec();
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (first, second) = if node.as_slice() <= sibling.as_slice() {
+            (node.as_slice(), sibling.as_slice())
+        } else {
+            (sibling.as_slice(), node.as_slice())
+        };
+        Sha256::new().chain(first).chain(second).finalize().to_vec()
+    });
+
+    if computed.as_slice() == root.as_slice() {
+        Ok(())
+    } else {
+        Err(ContractError::NotAllowlisted {})
+    }
+}
+
 fn check_wallet_limit(
     storage: &dyn Storage,
     owner: cosmwasm_std::Addr,
     limit: u32,
+    quantity: u32,
 ) -> Result<(), ContractError> {
     let num_wallet_tokens = tokens()
         .idx
@@ -105,35 +1151,428 @@ fn check_wallet_limit(
         .range(storage, None, None, Order::Ascending)
         .count();
 
-    if num_wallet_tokens >= limit as usize {
+    if num_wallet_tokens + quantity as usize > limit as usize {
         Err(ContractError::WalletLimit {})
     } else {
         Ok(())
     }
 }
 
+/// Checks that the receiver of a transfer stays within the wallet limit, if it applies to
+/// transfers. Transfers to or from exempt addresses aren't limited, so interns coming back from
+/// staking or a marketplace can't get stuck there.
+fn check_transfer_wallet_limit(
+    deps: Deps,
+    owner: &Addr,
+    receiver: &str,
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let is_exempt = |address: &str| {
+        address == config.staking_contract
+            || config
+                .wallet_limit_exempt
+                .iter()
+                .any(|exempt| exempt == address)
+    };
+    if !config.transfer_wallet_limit || is_exempt(receiver) || is_exempt(owner.as_str()) {
+        return Ok(());
+    }
+    let receiver = deps.api.addr_validate(receiver)?;
+    check_wallet_limit(deps.storage, receiver, config.wallet_limit, 1)
+}
+
 pub fn execute_update_config(
     deps: DepsMut,
     info: MessageInfo,
-    config: Config,
+    update: ConfigUpdate,
 ) -> Result<Response, ContractError> {
-    let owner = OWNER.load(deps.storage)?;
-    if info.sender != owner {
-        return Err(ContractError::Unauthorized {});
+    assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    // the committed metadata ordering wraps around the token supply, so it can't change
+    if update
+        .token_supply
+        .map_or(false, |supply| supply != config.token_supply)
+        && PROVENANCE_HASH.may_load(deps.storage)?.is_some()
+    {
+        return Err(ContractError::SupplyFrozen {});
     }
+
+    let mut changes = vec![];
+    let changed = &mut changes;
+    update_field(
+        changed,
+        "token_supply",
+        &mut config.token_supply,
+        update.token_supply,
+    )?;
+    update_field(
+        changed,
+        "wallet_limit",
+        &mut config.wallet_limit,
+        update.wallet_limit,
+    )?;
+    update_field(changed, "mint_fee", &mut config.mint_fee, update.mint_fee)?;
+    update_field(
+        changed,
+        "staking_contract",
+        &mut config.staking_contract,
+        update.staking_contract,
+    )?;
+    update_field(
+        changed,
+        "voucher_public_key",
+        &mut config.voucher_public_key,
+        update.voucher_public_key,
+    )?;
+    update_field(
+        changed,
+        "allowlist_root",
+        &mut config.allowlist_root,
+        update.allowlist_root,
+    )?;
+    update_field(
+        changed,
+        "allowlist_limit",
+        &mut config.allowlist_limit,
+        update.allowlist_limit,
+    )?;
+    update_field(
+        changed,
+        "mint_phases",
+        &mut config.mint_phases,
+        update.mint_phases,
+    )?;
+    update_field(
+        changed,
+        "reserved_supply",
+        &mut config.reserved_supply,
+        update.reserved_supply,
+    )?;
+    update_field(
+        changed,
+        "terrand_addr",
+        &mut config.terrand_addr,
+        update.terrand_addr,
+    )?;
+    update_field(
+        changed,
+        "rarity_tiers",
+        &mut config.rarity_tiers,
+        update.rarity_tiers,
+    )?;
+    update_field(
+        changed,
+        "placeholder",
+        &mut config.placeholder,
+        update.placeholder,
+    )?;
+    update_field(
+        changed,
+        "burn_redemption",
+        &mut config.burn_redemption,
+        update.burn_redemption,
+    )?;
+    update_field(changed, "royalty", &mut config.royalty, update.royalty)?;
+    update_field(changed, "base_uri", &mut config.base_uri, update.base_uri)?;
+    update_field(
+        changed,
+        "image_base_uri",
+        &mut config.image_base_uri,
+        update.image_base_uri,
+    )?;
+    update_field(
+        changed,
+        "render_svg",
+        &mut config.render_svg,
+        update.render_svg,
+    )?;
+    let level_curve = config.level_curve.clone();
+    update_field(
+        changed,
+        "level_curve",
+        &mut config.level_curve,
+        update.level_curve,
+    )?;
+    update_field(
+        changed,
+        "gold_token",
+        &mut config.gold_token,
+        update.gold_token,
+    )?;
+    update_field(
+        changed,
+        "transfer_wallet_limit",
+        &mut config.transfer_wallet_limit,
+        update.transfer_wallet_limit,
+    )?;
+    update_field(
+        changed,
+        "wallet_limit_exempt",
+        &mut config.wallet_limit_exempt,
+        update.wallet_limit_exempt,
+    )?;
+    update_field(
+        changed,
+        "profile_fee",
+        &mut config.profile_fee,
+        update.profile_fee,
+    )?;
+
+    validate_config(deps.as_ref(), &config)?;
     CONFIG.save(deps.storage, &config)?;
-    Ok(Response::new().add_attribute("action", "update_config"))
+
+    // levels are counted as interns change, so a new curve needs them all counted again
+    let held = Cw721Contract::<InternExtension, Empty>::default().token_count(deps.storage)?;
+    if config.level_curve != level_curve && held > 0 {
+        let mut totals = COLLECTION_TOTALS
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        totals.levels = vec![];
+        COLLECTION_TOTALS.save(deps.storage, &totals)?;
+        LEVEL_RECOUNT.save(deps.storage, &String::new())?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attributes(changes))
+}
+
+/// Counts the levels of the next `limit` interns after a level curve change, finishing the
+/// recount once every intern has been counted. Only the contract owner can recount.
+pub fn execute_recount_levels(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let last_counted = LEVEL_RECOUNT
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoLevelRecount {})?;
+    let config = CONFIG.load(deps.storage)?;
+    let limit = limit
+        .unwrap_or(DEFAULT_RECOUNT_LIMIT)
+        .min(MAX_RECOUNT_LIMIT) as usize;
+
+    let interns: StdResult<Vec<_>> = tokens()
+        .range(
+            deps.storage,
+            Some(Bound::exclusive(last_counted)),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect();
+    let interns = interns?;
+    let mut totals = COLLECTION_TOTALS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    for (_, token) in &interns {
+        count_level(&mut totals, &config.level_curve, token.extension.experience);
+    }
+    COLLECTION_TOTALS.save(deps.storage, &totals)?;
+
+    let finished = interns.len() < limit;
+    match interns.last() {
+        Some((token_id, _)) if !finished => LEVEL_RECOUNT.save(
+            deps.storage,
+            &String::from_utf8(token_id.clone()).map_err(StdError::from)?,
+        )?,
+        _ => LEVEL_RECOUNT.remove(deps.storage),
+    }
+    Ok(Response::new()
+        .add_attribute("action", "recount_levels")
+        .add_attribute("counted", interns.len().to_string())
+        .add_attribute("finished", finished.to_string()))
+}
+
+/// Sets a config field if the update has a different value for it, recording its JSON encoded
+/// old and new values as `old_{name}` and `new_{name}`.
+fn update_field<T>(
+    changes: &mut Vec<Attribute>,
+    name: &str,
+    field: &mut T,
+    value: Option<T>,
+) -> StdResult<()>
+where
+    T: Serialize + PartialEq,
+{
+    if let Some(value) = value {
+        if *field != value {
+            let json = |value: &T| -> StdResult<String> {
+                Ok(String::from_utf8_lossy(&to_vec(value)?).into_owned())
+            };
+            changes.push(Attribute::new(format!("old_{}", name), json(field)?));
+            changes.push(Attribute::new(format!("new_{}", name), json(&value)?));
+            *field = value;
+        }
+    }
+    Ok(())
+}
+
+pub fn execute_update_token_royalty(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    royalty: Option<Royalty>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let token_id = full_token_id(token_id)?;
+    tokens().load(deps.storage, &token_id)?;
+
+    validate_royalty(deps.as_ref(), &royalty)?;
+    match royalty {
+        Some(royalty) => TOKEN_ROYALTIES.save(deps.storage, &token_id, &royalty)?,
+        None => TOKEN_ROYALTIES.remove(deps.storage, &token_id),
+    }
+    Ok(Response::new()
+        .add_attribute("action", "update_token_royalty")
+        .add_attribute("token_id", numeric_token_id(token_id)?))
+}
+
+pub fn execute_update_token_image(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    image: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let token_id = full_token_id(token_id)?;
+    let mut token = tokens().load(deps.storage, &token_id)?;
+    token.image = image;
+    tokens().save(deps.storage, &token_id, &token)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_token_image")
+        .add_attribute("token_id", numeric_token_id(token_id)?))
+}
+
+fn validate_config(deps: Deps, config: &Config) -> StdResult<()> {
+    deps.api.addr_validate(&config.staking_contract)?;
+    if let Some(terrand_addr) = &config.terrand_addr {
+        deps.api.addr_validate(terrand_addr)?;
+    }
+    for address in &config.wallet_limit_exempt {
+        deps.api.addr_validate(address)?;
+    }
+    if config.mint_fee.denom.is_empty() {
+        return Err(StdError::generic_err("mint fee denom cannot be empty"));
+    }
+    // the redemption pool is held in the mint fee denom
+    let pool = REDEMPTION_POOL.may_load(deps.storage)?.unwrap_or_default();
+    if let Some(old_config) = CONFIG.may_load(deps.storage)? {
+        if !pool.is_zero() && config.mint_fee.denom != old_config.mint_fee.denom {
+            return Err(StdError::generic_err(
+                "mint fee denom cannot change while burn redemptions are reserved",
+            ));
+        }
+    }
+    if config.token_supply < minted_count(deps.storage)? {
+        return Err(StdError::generic_err(
+            "token supply cannot be less than the number of interns minted",
+        ));
+    }
+    if config.reserved_supply > config.token_supply {
+        return Err(StdError::generic_err(
+            "reserved supply cannot exceed the token supply",
+        ));
+    }
+    validate_mint_phases(&config.mint_phases)?;
+    validate_rarity_tiers(&config.rarity_tiers)?;
+    if config
+        .burn_redemption
+        .map_or(false, |rate| rate > Decimal::one())
+    {
+        return Err(StdError::generic_err("burn redemption cannot exceed 100%"));
+    }
+    validate_royalty(deps, &config.royalty)?;
+    config.level_curve.validate()?;
+    if let Some(gold_token) = &config.gold_token {
+        deps.api.addr_validate(&gold_token.address)?;
+        if gold_token.rate.is_zero() {
+            return Err(StdError::generic_err(
+                "gold conversion rate must be positive",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_mint_phases(phases: &[MintPhase]) -> StdResult<()> {
+    for (i, phase) in phases.iter().enumerate() {
+        if phase.name.is_empty() {
+            return Err(StdError::generic_err("mint phase name cannot be empty"));
+        }
+        if phases[..i].iter().any(|other| other.name == phase.name) {
+            return Err(StdError::generic_err("mint phase names must be unique"));
+        }
+        if phase
+            .end_time
+            .map_or(false, |end_time| end_time <= phase.start_time)
+        {
+            return Err(StdError::generic_err(
+                "mint phases must end after they start",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_rarity_tiers(tiers: &[RarityTier]) -> StdResult<()> {
+    if tiers.iter().any(|tier| tier.min_stamina > tier.max_stamina) {
+        return Err(StdError::generic_err(
+            "rarity tier minimum stamina cannot exceed its maximum",
+        ));
+    }
+    if !tiers.is_empty() && tiers.iter().all(|tier| tier.weight == 0) {
+        return Err(StdError::generic_err(
+            "at least one rarity tier needs a positive weight",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_royalty(deps: Deps, royalty: &Option<Royalty>) -> StdResult<()> {
+    if let Some(royalty) = royalty {
+        deps.api.addr_validate(&royalty.payment_address)?;
+        if royalty.basis_points > 10_000 {
+            return Err(StdError::generic_err(
+                "royalty cannot exceed 10000 basis points",
+            ));
+        }
+    }
+    Ok(())
 }
 
 pub fn execute_withdraw(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     amount: Vec<Coin>,
 ) -> Result<Response, ContractError> {
-    let owner = OWNER.load(deps.storage)?;
-    if info.sender != owner {
-        return Err(ContractError::Unauthorized {});
+    let owner = assert_owner(deps.storage, &info.sender)?;
+    if !PAYEES
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .is_empty()
+    {
+        return Err(ContractError::PayeesConfigured {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let pool = REDEMPTION_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let withdrawn: Uint128 = amount
+        .iter()
+        .filter(|coin| coin.denom == config.mint_fee.denom)
+        .map(|coin| coin.amount)
+        .sum();
+    if !pool.is_zero() && !withdrawn.is_zero() {
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &config.mint_fee.denom)?;
+        if withdrawn > balance.amount.saturating_sub(pool) {
+            return Err(ContractError::RedemptionReserved {});
+        }
     }
 
     Ok(Response::new().add_message(BankMsg::Send {
@@ -142,10 +1581,198 @@ pub fn execute_withdraw(
     }))
 }
 
-pub fn cw721_base_execute(
+pub fn execute_update_payees(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payees: Vec<Payee>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    for (i, payee) in payees.iter().enumerate() {
+        deps.api.addr_validate(&payee.address)?;
+        if payee.shares == 0 {
+            return Err(StdError::generic_err("payee shares must be positive").into());
+        }
+        if payees[..i]
+            .iter()
+            .any(|other| other.address == payee.address)
+        {
+            return Err(StdError::generic_err("payees must be unique").into());
+        }
+    }
+
+    // settle up with the current payees, after which nothing has been released under the
+    // new shares
+    let current = PAYEES.may_load(deps.storage)?.unwrap_or_default();
+    let messages = release_payments(deps.branch(), &env, &current)?;
+    let denoms = TOTAL_RELEASED
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(String::from_utf8)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(StdError::from)?;
+    for denom in denoms {
+        for payee in &current {
+            RELEASED.remove(deps.storage, (&payee.address, &denom));
+        }
+        TOTAL_RELEASED.remove(deps.storage, &denom);
+    }
+    PAYEES.save(deps.storage, &payees)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "update_payees")
+        .add_attribute("payees", payees.len().to_string()))
+}
+
+pub fn execute_distribute(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let payees = PAYEES.may_load(deps.storage)?.unwrap_or_default();
+    if payees.is_empty() {
+        return Err(ContractError::NoPayees {});
+    }
+    let messages = release_payments(deps, &env, &payees)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "distribute"))
+}
+
+/// Pays every payee what they're owed, recording the amounts as released.
+fn release_payments(deps: DepsMut, env: &Env, payees: &[Payee]) -> StdResult<Vec<BankMsg>> {
+    let pending = pending_payments(deps.as_ref(), &env.contract.address, payees)?;
+    let mut messages = vec![];
+    for (payee, amount) in payees.iter().zip(pending) {
+        if amount.is_empty() {
+            continue;
+        }
+        for coin in &amount {
+            let add = |released: Option<Uint128>| -> StdResult<_> {
+                Ok(released.unwrap_or_default() + coin.amount)
+            };
+            RELEASED.update(deps.storage, (&payee.address, &coin.denom), add)?;
+            TOTAL_RELEASED.update(deps.storage, &coin.denom, add)?;
+        }
+        messages.push(BankMsg::Send {
+            to_address: payee.address.clone(),
+            amount,
+        });
+    }
+    Ok(messages)
+}
+
+/// Returns the contract owner if it's the sender, failing otherwise (and always once ownership
+/// has been renounced).
+fn assert_owner(storage: &dyn Storage, sender: &Addr) -> Result<String, ContractError> {
+    match OWNER.may_load(storage)? {
+        Some(owner) if *sender == owner => Ok(owner),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    minting: Option<bool>,
+    transfers: Option<bool>,
+    trait_updates: Option<bool>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let mut paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    paused.minting = minting.unwrap_or(paused.minting);
+    paused.transfers = transfers.unwrap_or(paused.transfers);
+    paused.trait_updates = trait_updates.unwrap_or(paused.trait_updates);
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_paused")
+        .add_attribute("minting", paused.minting.to_string())
+        .add_attribute("transfers", paused.transfers.to_string())
+        .add_attribute("trait_updates", paused.trait_updates.to_string()))
+}
+
+/// Fails if the owner has paused the operation, as selected from the pause state.
+fn check_not_paused<F>(
+    storage: &dyn Storage,
+    operation: &str,
+    paused: F,
+) -> Result<(), ContractError>
+where
+    F: FnOnce(&PauseState) -> bool,
+{
+    let state = PAUSED.may_load(storage)?.unwrap_or_default();
+    if paused(&state) {
+        return Err(ContractError::Paused {
+            operation: operation.to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn execute_propose_new_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    new_owner: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let expires = expires.unwrap_or_else(|| {
+        Expiration::AtTime(env.block.time.plus_seconds(OWNERSHIP_PROPOSAL_DURATION))
+    });
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::OwnershipProposalAlreadyExpired {});
+    }
+    let address = deps.api.addr_validate(&new_owner)?;
+    PENDING_OWNER.save(
+        deps.storage,
+        &PendingOwner {
+            address: address.to_string(),
+            expires,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_new_owner")
+        .add_attribute("new_owner", address)
+        .add_attribute("expires", expires.to_string()))
+}
+
+pub fn execute_accept_ownership(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_OWNER
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingOwner {})?;
+    if info.sender != pending.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    if pending.expires.is_expired(&env.block) {
+        return Err(ContractError::OwnershipProposalExpired {});
+    }
+    OWNER.save(deps.storage, &pending.address)?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_ownership")
+        .add_attribute("owner", pending.address))
+}
+
+pub fn execute_renounce_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    OWNER.remove(deps.storage);
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "renounce_ownership"))
+}
+
+pub fn cw721_base_execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     let cw721_contract = Cw721Contract::<InternExtension, Empty>::default();
@@ -183,17 +1810,50 @@ pub fn cw721_base_execute(
         _ => cw721_msg,
     };
 
+    let transferred = match &cw721_msg_full_token_id {
+        Cw721ExecuteMsg::TransferNft {
+            recipient: receiver,
+            token_id,
+        }
+        | Cw721ExecuteMsg::SendNft {
+            contract: receiver,
+            token_id,
+            ..
+        } => {
+            check_not_paused(deps.storage, "transfers", |paused| paused.transfers)?;
+            let token = tokens().may_load(deps.storage, token_id)?;
+            if let Some(token) = &token {
+                check_transfer_wallet_limit(deps.as_ref(), &token.owner, receiver)?;
+            }
+            token.map(|token| (token_id.clone(), token))
+        }
+        _ => None,
+    };
+
     let mut response = (match cw721_msg_full_token_id {
         Cw721ExecuteMsg::SendNft {
             contract,
             token_id,
             msg,
-        } => execute_send_nft(deps, env, info, contract, token_id, msg),
+        } => execute_send_nft(deps.branch(), env, info, contract, token_id, msg),
         _ => cw721_contract
-            .execute(deps, env, info, cw721_msg_full_token_id)
+            .execute(deps.branch(), env, info, cw721_msg_full_token_id)
             .map_err(|err| err.into()),
     })?;
 
+    // cw721-base moves the token on its own, so the holder counts are updated here
+    if let Some((token_id, token)) = transferred {
+        let config = CONFIG.load(deps.storage)?;
+        let new_token = tokens().load(deps.storage, &token_id)?;
+        update_collection_totals(
+            deps.storage,
+            &config.level_curve,
+            &token_id,
+            Some(&token),
+            Some(&new_token),
+        )?;
+    }
+
     response.attributes = response
         .attributes
         .iter()
@@ -250,7 +1910,7 @@ mod test {
     use cosmwasm_std::{to_binary, Addr};
     use cw721::{Cw721ReceiveMsg, Expiration};
     use cw721_base::state::Approval;
-    use internnft::nft::InternTokenInfo;
+    use internnft::nft::LevelCurve;
 
     const ADDR1: &str = "addr1";
     const ADDR2: &str = "addr2";
@@ -285,6 +1945,31 @@ mod test {
     }
 
     fn setup_storage(deps: DepsMut) {
+        let config = Config {
+            token_supply: 10,
+            wallet_limit: 5,
+            mint_fee: Coin::new(0, "uluna"),
+            staking_contract: "staking_contract".to_string(),
+            voucher_public_key: None,
+            allowlist_root: None,
+            allowlist_limit: 0,
+            mint_phases: vec![],
+            reserved_supply: 0,
+            terrand_addr: None,
+            rarity_tiers: vec![],
+            placeholder: None,
+            burn_redemption: None,
+            royalty: None,
+            base_uri: None,
+            image_base_uri: None,
+            render_svg: false,
+            level_curve: LevelCurve::default(),
+            gold_token: None,
+            transfer_wallet_limit: false,
+            wallet_limit_exempt: vec![],
+            profile_fee: 0,
+        };
+        CONFIG.save(deps.storage, &config).unwrap();
         for token in token_examples().iter() {
             tokens().save(deps.storage, &token.name, token).unwrap();
         }
@@ -5,3 +5,4 @@ pub mod query;
 pub mod state;
 
 mod contract_tests;
+mod mock_querier;
@@ -0,0 +1,88 @@
+#![cfg(test)]
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+};
+use internnft::staking::{ContractQuery, GetRandomResponse};
+
+pub const TERRAND: &str = "terrand";
+
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    // latest terrand round with published randomness
+    terrand_round: u64,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr == TERRAND =>
+            {
+                match from_binary(msg).unwrap() {
+                    ContractQuery::GetRandomness { round } if round > self.terrand_round => {
+                        SystemResult::Ok(ContractResult::Err(format!(
+                            "round {} is not published yet",
+                            round
+                        )))
+                    }
+                    ContractQuery::GetRandomness { .. } => {
+                        SystemResult::Ok(ContractResult::from(to_binary(&GetRandomResponse {
+                            randomness: Binary::from_base64(
+                                "yTBW2ubloeFa+ZRh08Jt+4jVQHHGMX4s3j8mTYKc3oQ=",
+                            )
+                            .unwrap(),
+                            worker: "terra1x46rqay4d3cssq8gxxvqz8xt6nwlz4td20k38v".to_string(),
+                        })))
+                    }
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            terrand_round: u64::MAX,
+        }
+    }
+
+    pub fn update_terrand_round(&mut self, round: u64) {
+        self.terrand_round = round;
+    }
+
+    pub fn update_balance(&mut self, addr: &str, balance: Vec<Coin>) {
+        self.base.update_balance(addr, balance);
+    }
+}
@@ -1,26 +1,227 @@
-use cosmwasm_std::{to_binary, Binary, BlockInfo, Deps, Empty, Env, Order, StdError, StdResult};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, BlockInfo, Coin, Decimal, Deps, Empty, Env, Order, StdError,
+    StdResult, Uint128,
+};
 use cw721::{NumTokensResponse, OwnerOfResponse, TokensResponse};
 use cw721_base::{msg::QueryMsg as Cw721QueryMsg, Cw721Contract};
-use cw_storage_plus::Bound;
+use cw_storage_plus::{Bound, U64Key};
 use internnft::nft::{
-    full_token_id, numeric_token_id, Config, Cw721AllNftInfoResponse, Cw721NftInfoResponse,
-    InternExtension, InternTokenInfo, InternTokensResponse, QueryMsg,
+    full_token_id, numeric_token_id, AllowlistMintsResponse, BurnedTokensResponse,
+    CheckRoyaltiesResponse, CollectionStatsResponse, Config, Cw721AllNftInfoResponse,
+    Cw721NftInfoResponse, InternExtension, InternNftInfoResponse, InternTokenEntry,
+    InternTokenInfo, InternTokensResponse, InternTrait, LeaderboardEntry, LeaderboardResponse,
+    LevelCount, MetadataContext, MintPhase, MintStatusResponse, OwnerResponse, PauseState,
+    PayeeInfo, PayeesResponse, ProvenanceResponse, QueryMsg, RoyaltiesInfoResponse, SortOrder,
+    TraitHistoryResponse, TraitUpdater, UpdatersResponse,
 };
 
-use crate::state::{tokens, CONFIG};
+use crate::state::{
+    leaderboard_key, metadata_context, minted_count, pending_payments, public_supply,
+    token_royalty, tokens, ALLOWLIST_MINTS, BURNED, COLLECTION_TOTALS, CONFIG, HOLDER_BALANCES,
+    LEVEL_RECOUNT, OWNER, PAUSED, PAYEES, PENDING_OWNER, PHASE_MINTS, PHASE_SUPPLY,
+    PROVENANCE_HASH, RARITIES, RELEASED, REVEAL_OFFSET, REVEAL_ROUND, TOKEN_ROYALTIES,
+    TRAIT_HISTORY, UPDATERS,
+};
 
 pub fn query_config(deps: Deps) -> StdResult<Config> {
     CONFIG.load(deps.storage)
 }
 
-pub fn query_intern_nft_info(deps: Deps, token_id: String) -> StdResult<InternTokenInfo> {
+pub fn query_owner(deps: Deps) -> StdResult<OwnerResponse> {
+    Ok(OwnerResponse {
+        owner: OWNER.may_load(deps.storage)?,
+        pending_owner: PENDING_OWNER.may_load(deps.storage)?,
+    })
+}
+
+pub fn query_pause_state(deps: Deps) -> StdResult<PauseState> {
+    Ok(PAUSED.may_load(deps.storage)?.unwrap_or_default())
+}
+
+pub fn query_collection_stats(deps: Deps) -> StdResult<CollectionStatsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let minted = minted_count(deps.storage)?;
+    let totals = COLLECTION_TOTALS
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let held = Cw721Contract::<InternExtension, Empty>::default().token_count(deps.storage)?;
+    let average = |total: Uint128| match held {
+        0 => Decimal::zero(),
+        _ => Decimal::from_ratio(total, held),
+    };
+
+    // the staking contract and marketplaces hold interns on behalf of their owners
+    let mut custodians = config.wallet_limit_exempt.clone();
+    custodians.push(config.staking_contract.clone());
+    custodians.sort();
+    custodians.dedup();
+    let mut holders = totals.holders;
+    for custodian in custodians {
+        if HOLDER_BALANCES.has(deps.storage, &Addr::unchecked(custodian)) {
+            holders = holders.saturating_sub(1);
+        }
+    }
+
+    Ok(CollectionStatsResponse {
+        minted,
+        remaining_supply: config.token_supply.saturating_sub(minted),
+        holders,
+        total_experience: totals.experience,
+        total_gold: totals.gold,
+        total_stamina: totals.stamina,
+        average_experience: average(totals.experience),
+        average_gold: average(totals.gold),
+        average_stamina: average(totals.stamina),
+        levels: totals
+            .levels
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(i, count)| LevelCount {
+                level: i as u32 + 1,
+                count: *count,
+            })
+            .collect(),
+        recounting_levels: LEVEL_RECOUNT.may_load(deps.storage)?.is_some(),
+    })
+}
+
+pub fn query_payees(deps: Deps, env: Env) -> StdResult<PayeesResponse> {
+    let payees = PAYEES.may_load(deps.storage)?.unwrap_or_default();
+    let pending = pending_payments(deps, &env.contract.address, &payees)?;
+    let payees = payees
+        .into_iter()
+        .zip(pending)
+        .map(|(payee, pending)| {
+            let released: StdResult<Vec<_>> = RELEASED
+                .prefix(&payee.address)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| {
+                    let (denom, amount) = item?;
+                    Ok(Coin {
+                        denom: String::from_utf8(denom)?,
+                        amount,
+                    })
+                })
+                .collect();
+            Ok(PayeeInfo {
+                address: payee.address,
+                shares: payee.shares,
+                released: released?,
+                pending,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(PayeesResponse { payees })
+}
+
+pub fn query_intern_nft_info(deps: Deps, token_id: String) -> StdResult<InternNftInfoResponse> {
+    let token_id = full_token_id(token_id)?;
     let token = tokens().load(deps.storage, &token_id)?;
-    Ok(token)
+    let context = token_metadata_context(deps, &token_id)?;
+    let experience = token.extension.experience;
+    let (next_level_experience, level_progress) = context.level_curve.progress(experience);
+    let token_uri = token.as_cw721_nft_info(&token_id, &context).token_uri;
+    let token = context.display(&token_id, &token);
+    Ok(InternNftInfoResponse {
+        token_uri,
+        owner: token.owner,
+        approvals: token.approvals,
+        name: token.name,
+        description: token.description,
+        image: token.image,
+        extension: token.extension,
+        rarity: RARITIES.may_load(deps.storage, &token_id)?,
+        level: context.level_curve.level(experience),
+        next_level_experience,
+        level_progress,
+    })
+}
+
+pub fn query_provenance(deps: Deps) -> StdResult<ProvenanceResponse> {
+    let offset = REVEAL_OFFSET.may_load(deps.storage)?;
+    Ok(ProvenanceResponse {
+        provenance_hash: PROVENANCE_HASH.may_load(deps.storage)?,
+        revealed: metadata_context(deps.storage)?.placeholder.is_none(),
+        reveal_round: REVEAL_ROUND.may_load(deps.storage)?,
+        offset,
+    })
 }
 
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
 
+pub fn query_trait_history(
+    deps: Deps,
+    token_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TraitHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|id| Bound::exclusive(U64Key::new(id)));
+
+    let token_id = full_token_id(token_id)?;
+    let changes: StdResult<Vec<_>> = TRAIT_HISTORY
+        .prefix(&token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, change)| change))
+        .collect();
+    Ok(TraitHistoryResponse { changes: changes? })
+}
+
+pub fn query_updaters(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<UpdatersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let updaters: StdResult<Vec<_>> = UPDATERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, traits) = item?;
+            Ok(TraitUpdater {
+                address: String::from_utf8(address)?,
+                traits,
+            })
+        })
+        .collect();
+    Ok(UpdatersResponse {
+        updaters: updaters?,
+    })
+}
+
+pub fn query_burned_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<BurnedTokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after {
+        Some(start_after) => {
+            let start_after = start_after
+                .parse::<u64>()
+                .map_err(|_| StdError::generic_err("expected numeric token identifier"))?;
+            Some(Bound::exclusive(U64Key::new(start_after)))
+        }
+        None => None,
+    };
+
+    let tokens: StdResult<Vec<_>> = BURNED
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|key| {
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&key);
+            Ok(u64::from_be_bytes(id).to_string())
+        })
+        .collect();
+    Ok(BurnedTokensResponse { tokens: tokens? })
+}
+
 pub fn query_intern_tokens(
     deps: Deps,
     owner: String,
@@ -28,16 +229,25 @@ pub fn query_intern_tokens(
     limit: Option<u32>,
 ) -> StdResult<InternTokensResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(Bound::exclusive);
+    let start = start_after.map(full_token_id).transpose()?;
+    let start = start.map(Bound::exclusive);
 
     let owner = deps.api.addr_validate(&owner)?;
+    let context = metadata_context(deps.storage)?;
     let tokens: StdResult<Vec<_>> = tokens()
         .idx
         .owner
         .prefix(owner)
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
-        .map(|item| item.map(|(_, token)| token))
+        .map(|item| {
+            let (token_id, token) = item?;
+            let token_id = String::from_utf8(token_id)?;
+            Ok(InternTokenEntry {
+                info: context.display(&token_id, &token),
+                token_id: numeric_token_id(token_id)?,
+            })
+        })
         .collect();
     Ok(InternTokensResponse { tokens: tokens? })
 }
@@ -48,16 +258,79 @@ pub fn query_all_intern_tokens(
     limit: Option<u32>,
 ) -> StdResult<InternTokensResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-    let start = start_after.map(Bound::exclusive);
+    let start = start_after.map(full_token_id).transpose()?;
+    let start = start.map(Bound::exclusive);
 
+    let context = metadata_context(deps.storage)?;
     let tokens: StdResult<Vec<_>> = tokens()
         .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
-        .map(|item| item.map(|(_, token)| token))
+        .map(|item| {
+            let (token_id, token) = item?;
+            let token_id = String::from_utf8(token_id)?;
+            Ok(InternTokenEntry {
+                info: context.display(&token_id, &token),
+                token_id: numeric_token_id(token_id)?,
+            })
+        })
         .collect();
     Ok(InternTokensResponse { tokens: tokens? })
 }
 
+pub fn query_leaderboard(
+    deps: Deps,
+    intern_trait: InternTrait,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    order: Option<SortOrder>,
+) -> StdResult<LeaderboardResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let indexes = tokens().idx;
+    let index = match intern_trait {
+        InternTrait::Experience => &indexes.experience,
+        InternTrait::Gold => &indexes.gold,
+        InternTrait::Stamina => {
+            return Err(StdError::generic_err("no leaderboard for stamina"));
+        }
+    };
+
+    // resume right after the last intern of the previous page, ranked by its current trait value
+    let start = match start_after {
+        Some(token_id) => {
+            let token_id = full_token_id(token_id)?;
+            let token = tokens().load(deps.storage, &token_id)?;
+            let value = match intern_trait {
+                InternTrait::Experience => token.extension.experience,
+                _ => token.extension.gold,
+            };
+            let token_id = token_id.into_bytes();
+            Some(Bound::exclusive(
+                index.index_key((leaderboard_key(value, &token_id), token_id)),
+            ))
+        }
+        None => None,
+    };
+    let (min, max, order) = match order.unwrap_or(SortOrder::Descending) {
+        SortOrder::Ascending => (start, None, Order::Ascending),
+        SortOrder::Descending => (None, start, Order::Descending),
+    };
+
+    let context = metadata_context(deps.storage)?;
+    let entries: StdResult<Vec<_>> = index
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|item| {
+            let (token_id, token) = item?;
+            let token_id = String::from_utf8(token_id)?;
+            Ok(LeaderboardEntry {
+                token: context.display(&token_id, &token),
+                token_id: numeric_token_id(token_id)?,
+            })
+        })
+        .collect();
+    Ok(LeaderboardResponse { entries: entries? })
+}
+
 pub fn query_num_tokens_for_owner(deps: Deps, owner: String) -> StdResult<NumTokensResponse> {
     let owner = deps.api.addr_validate(&owner)?;
     let count = tokens()
@@ -69,6 +342,104 @@ pub fn query_num_tokens_for_owner(deps: Deps, owner: String) -> StdResult<NumTok
     Ok(NumTokensResponse { count })
 }
 
+pub fn query_allowlist_mints(deps: Deps, address: String) -> StdResult<AllowlistMintsResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let address = deps.api.addr_validate(&address)?;
+    let minted = ALLOWLIST_MINTS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    Ok(AllowlistMintsResponse {
+        minted,
+        remaining: config.allowlist_limit.saturating_sub(minted),
+    })
+}
+
+pub fn query_royalty_info(
+    deps: Deps,
+    token_id: String,
+    sale_price: Uint128,
+) -> StdResult<RoyaltiesInfoResponse> {
+    let token_id = full_token_id(token_id)?;
+    tokens().load(deps.storage, &token_id)?;
+    Ok(match token_royalty(deps.storage, &token_id)? {
+        Some(royalty) => RoyaltiesInfoResponse {
+            royalty_amount: royalty.amount(sale_price),
+            address: royalty.payment_address,
+        },
+        None => RoyaltiesInfoResponse {
+            address: "".to_string(),
+            royalty_amount: Uint128::zero(),
+        },
+    })
+}
+
+pub fn query_check_royalties(deps: Deps) -> StdResult<CheckRoyaltiesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let overridden = TOKEN_ROYALTIES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    Ok(CheckRoyaltiesResponse {
+        royalty_payments: config.royalty.is_some() || overridden,
+    })
+}
+
+pub fn query_mint_status(
+    deps: Deps,
+    env: Env,
+    address: Option<String>,
+) -> StdResult<MintStatusResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let phase = config.active_mint_phase(env.block.time).cloned();
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    let open = !paused.minting && (config.mint_phases.is_empty() || phase.is_some());
+
+    let mut remaining_supply = public_supply(deps.storage, &config)?;
+    if let Some(MintPhase {
+        name,
+        supply: Some(supply),
+        ..
+    }) = &phase
+    {
+        let minted = PHASE_SUPPLY
+            .may_load(deps.storage, name)?
+            .unwrap_or_default();
+        remaining_supply = remaining_supply.min(supply.saturating_sub(minted));
+    }
+
+    let remaining_allowance = match address {
+        Some(address) if open => {
+            let address = deps.api.addr_validate(&address)?;
+            let held = query_num_tokens_for_owner(deps, address.to_string())?.count as u32;
+            let mut allowance = match &phase {
+                Some(phase) if phase.allowlist => {
+                    query_allowlist_mints(deps, address.to_string())?.remaining
+                }
+                _ => config.wallet_limit.saturating_sub(held),
+            };
+            if let Some(phase) = &phase {
+                let minted = PHASE_MINTS
+                    .may_load(deps.storage, (&phase.name, &address))?
+                    .unwrap_or_default();
+                allowance = allowance.min(phase.wallet_limit.saturating_sub(minted));
+            }
+            Some(allowance.min(remaining_supply.min(u32::MAX as u64) as u32))
+        }
+        Some(_) => Some(0),
+        None => None,
+    };
+
+    Ok(MintStatusResponse {
+        open,
+        price: phase
+            .as_ref()
+            .map_or(config.mint_fee.clone(), |phase| phase.price.clone()),
+        phase,
+        remaining_supply,
+        remaining_allowance,
+    })
+}
+
 pub fn cw721_base_query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let cw721_contract = Cw721Contract::<InternExtension, Empty>::default();
 
@@ -109,7 +480,14 @@ pub fn cw721_base_query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary
 
 pub fn query_nft_info(deps: Deps, _env: Env, token_id: String) -> StdResult<Cw721NftInfoResponse> {
     let info = tokens().load(deps.storage, &token_id)?;
-    Ok(info.as_cw721_nft_info())
+    Ok(info.as_cw721_nft_info(&token_id, &token_metadata_context(deps, &token_id)?))
+}
+
+fn token_metadata_context(deps: Deps, token_id: &str) -> StdResult<MetadataContext> {
+    Ok(MetadataContext {
+        royalty: token_royalty(deps.storage, token_id)?,
+        ..metadata_context(deps.storage)?
+    })
 }
 
 pub fn query_all_nft_info(
@@ -124,7 +502,7 @@ pub fn query_all_nft_info(
             owner: info.owner.to_string(),
             approvals: humanize_approvals(&env.block, &info, include_expired),
         },
-        info: info.as_cw721_nft_info(),
+        info: info.as_cw721_nft_info(&token_id, &token_metadata_context(deps, &token_id)?),
     })
 }
 
@@ -218,8 +596,8 @@ mod test {
     use super::*;
 
     use cosmwasm_std::testing::{mock_dependencies, mock_env};
-    use cosmwasm_std::{from_binary, Addr, DepsMut};
-    use internnft::nft::{Cw721AllNftInfoResponse, Cw721Metadata, Cw721Trait};
+    use cosmwasm_std::{from_binary, Addr, Coin, DepsMut};
+    use internnft::nft::{Cw721AllNftInfoResponse, Cw721Metadata, Cw721Trait, LevelCurve};
 
     const ADDR1: &str = "addr1";
     const ADDR2: &str = "addr2";
@@ -254,6 +632,31 @@ mod test {
     }
 
     fn setup_storage(deps: DepsMut) {
+        let config = Config {
+            token_supply: 10,
+            wallet_limit: 5,
+            mint_fee: Coin::new(0, "uluna"),
+            staking_contract: "staking_contract".to_string(),
+            voucher_public_key: None,
+            allowlist_root: None,
+            allowlist_limit: 0,
+            mint_phases: vec![],
+            reserved_supply: 0,
+            terrand_addr: None,
+            rarity_tiers: vec![],
+            placeholder: None,
+            burn_redemption: None,
+            royalty: None,
+            base_uri: None,
+            image_base_uri: None,
+            render_svg: false,
+            level_curve: LevelCurve::default(),
+            gold_token: None,
+            transfer_wallet_limit: false,
+            wallet_limit_exempt: vec![],
+            profile_fee: 0,
+        };
+        CONFIG.save(deps.storage, &config).unwrap();
         for token in token_examples().iter() {
             tokens().save(deps.storage, &token.name, token).unwrap();
         }
@@ -277,6 +680,11 @@ mod test {
                 description: Some("".to_string()),
                 name: Some("intern #1".to_string()),
                 attributes: Some(vec![
+                    Cw721Trait {
+                        display_type: None,
+                        trait_type: "level".to_string(),
+                        value: "1".to_string(),
+                    },
                     Cw721Trait {
                         display_type: None,
                         trait_type: "experience".to_string(),
@@ -296,6 +704,8 @@ mod test {
                 background_color: None,
                 animation_url: None,
                 youtube_url: None,
+                royalty_percentage: None,
+                royalty_payment_address: None,
             },
         };
 
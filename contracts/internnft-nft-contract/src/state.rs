@@ -1,16 +1,27 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
-use internnft::nft::{Config, InternTokenInfo};
+use cosmwasm_std::{Addr, Coin, Deps, Empty, StdResult, Storage, Uint128};
+use cw721_base::Cw721Contract;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key, UniqueIndex};
+use internnft::nft::{
+    numeric_token_id, CollectionTotals, Config, InternExtension, InternTokenInfo, InternTrait,
+    LevelCurve, MetadataContext, PauseState, Payee, PendingOwner, PendingRoll, Royalty,
+    TraitChange,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
 pub struct TokenIndexes<'a> {
     pub owner: MultiIndex<'a, (Addr, Vec<u8>), InternTokenInfo>,
+    // lowercased, so that names are unique regardless of case
+    pub name: UniqueIndex<'a, Vec<u8>, InternTokenInfo>,
+    // leaderboards, kept in step with trait updates since those replace the token
+    pub experience: MultiIndex<'a, (Vec<u8>, Vec<u8>), InternTokenInfo>,
+    pub gold: MultiIndex<'a, (Vec<u8>, Vec<u8>), InternTokenInfo>,
 }
 
 impl<'a> IndexList<InternTokenInfo> for TokenIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<InternTokenInfo>> + '_> {
-        let v: Vec<&dyn Index<InternTokenInfo>> = vec![&self.owner];
+        let v: Vec<&dyn Index<InternTokenInfo>> =
+            vec![&self.owner, &self.name, &self.experience, &self.gold];
         Box::new(v.into_iter())
     }
 }
@@ -22,8 +33,264 @@ pub fn tokens<'a>() -> IndexedMap<'a, &'a str, InternTokenInfo, TokenIndexes<'a>
             "tokens",
             "tokens__owner",
         ),
+        name: UniqueIndex::new(
+            |d: &InternTokenInfo| d.name.to_lowercase().into_bytes(),
+            "tokens__name",
+        ),
+        experience: MultiIndex::new(
+            |d: &InternTokenInfo, k: Vec<u8>| (leaderboard_key(d.extension.experience, &k), k),
+            "tokens",
+            "tokens__experience",
+        ),
+        gold: MultiIndex::new(
+            |d: &InternTokenInfo, k: Vec<u8>| (leaderboard_key(d.extension.gold, &k), k),
+            "tokens",
+            "tokens__gold",
+        ),
     };
     IndexedMap::new("tokens", indexes)
 }
 
+/// Returns the leaderboard index key of a token: its trait value followed by its numeric id,
+/// both big-endian so that tokens sort by value and then by id ("intern #2" before "intern #10").
+pub fn leaderboard_key(value: u64, token_id: &[u8]) -> Vec<u8> {
+    let id = String::from_utf8(token_id.to_vec())
+        .ok()
+        .and_then(|token_id| numeric_token_id(token_id).ok())
+        .and_then(|id| id.parse::<u64>().ok())
+        .unwrap_or_default();
+    [value.to_be_bytes(), id.to_be_bytes()].concat()
+}
+
+// removed when ownership is renounced
 pub const OWNER: Item<String> = Item::new("owner");
+
+// ownership transfer waiting to be accepted by the proposed owner
+pub const PENDING_OWNER: Item<PendingOwner> = Item::new("pending_owner");
+
+// operations paused by the owner, nothing is paused if unset
+pub const PAUSED: Item<PauseState> = Item::new("paused");
+
+// nonces of all the mint vouchers that have been redeemed
+pub const USED_NONCES: Map<U64Key, bool> = Map::new("used_nonces");
+
+// number of allowlist mints made by each allowlisted address
+pub const ALLOWLIST_MINTS: Map<&Addr, u32> = Map::new("allowlist_mints");
+
+// number of interns minted by each address during each mint phase
+pub const PHASE_MINTS: Map<(&str, &Addr), u32> = Map::new("phase_mints");
+
+// number of interns minted during each mint phase
+pub const PHASE_SUPPLY: Map<&str, u64> = Map::new("phase_supply");
+
+// number of interns minted through airdrops
+pub const AIRDROPPED: Item<u64> = Item::new("airdropped");
+
+// game contracts allowed to update traits, along with the traits they can update
+pub const UPDATERS: Map<&Addr, Vec<InternTrait>> = Map::new("updaters");
+
+// every change made to each token's traits, by token id and sequence number
+pub const TRAIT_HISTORY: Map<(&str, U64Key), TraitChange> = Map::new("trait_history");
+
+// number of trait changes recorded for each token
+pub const TRAIT_HISTORY_COUNT: Map<&str, u64> = Map::new("trait_history_count");
+
+// starting trait rolls each intern is still waiting on, by token id
+pub const PENDING_ROLLS: Map<&str, PendingRoll> = Map::new("pending_rolls");
+
+// rarity tier each intern rolled, by token id
+pub const RARITIES: Map<&str, String> = Map::new("rarities");
+
+// burned token ids along with the address that burned them
+pub const BURNED: Map<U64Key, Addr> = Map::new("burned");
+
+// number of interns burned so far
+pub const BURN_COUNT: Item<u64> = Item::new("burn_count");
+
+/// Returns the number of interns ever minted. Burned interns still count, so that their ids
+/// are never reissued and they keep counting against the token supply.
+pub fn minted_count(storage: &dyn Storage) -> StdResult<u64> {
+    let burned = BURN_COUNT.may_load(storage)?.unwrap_or_default();
+    let held = Cw721Contract::<InternExtension, Empty>::default().token_count(storage)?;
+    Ok(held + burned)
+}
+
+/// Returns the number of interns that can be minted outside of airdrops, which excludes
+/// whatever is left of the reserved supply.
+pub fn public_supply(storage: &dyn Storage, config: &Config) -> StdResult<u64> {
+    let airdropped = AIRDROPPED.may_load(storage)?.unwrap_or_default();
+    let reserved = config.reserved_supply.saturating_sub(airdropped);
+    let minted = minted_count(storage)?;
+    Ok(config
+        .token_supply
+        .saturating_sub(reserved)
+        .saturating_sub(minted))
+}
+
+// running totals behind the collection stats
+pub const COLLECTION_TOTALS: Item<CollectionTotals> = Item::new("collection_totals");
+
+// number of interns held by each address, removed once it drops to zero
+pub const HOLDER_BALANCES: Map<&Addr, u64> = Map::new("holder_balances");
+
+// while levels are being recounted after a level curve change, the id of the last token counted
+// so far (empty before the first one); only tokens up to it are included in the level counts
+pub const LEVEL_RECOUNT: Item<String> = Item::new("level_recount");
+
+/// Updates the collection totals for a token that's minted (no old token), burned (no new
+/// token), transferred or changed, the same way `replace` updates the token indexes.
+pub fn update_collection_totals(
+    storage: &mut dyn Storage,
+    level_curve: &LevelCurve,
+    token_id: &str,
+    old: Option<&InternTokenInfo>,
+    new: Option<&InternTokenInfo>,
+) -> StdResult<()> {
+    let mut totals = COLLECTION_TOTALS.may_load(storage)?.unwrap_or_default();
+    let levels_counted = LEVEL_RECOUNT
+        .may_load(storage)?
+        .map_or(true, |counted| token_id <= counted.as_str());
+    if let Some(old) = old {
+        if new.map_or(true, |new| new.owner != old.owner) {
+            let balance = HOLDER_BALANCES
+                .may_load(storage, &old.owner)?
+                .unwrap_or_default();
+            if balance <= 1 {
+                HOLDER_BALANCES.remove(storage, &old.owner);
+                totals.holders = totals.holders.saturating_sub(1);
+            } else {
+                HOLDER_BALANCES.save(storage, &old.owner, &(balance - 1))?;
+            }
+        }
+        let traits = &old.extension;
+        totals.experience = totals.experience.saturating_sub(traits.experience.into());
+        totals.gold = totals.gold.saturating_sub(traits.gold.into());
+        totals.stamina = totals.stamina.saturating_sub(traits.stamina.into());
+        if levels_counted {
+            let level = level_curve.level(traits.experience) as usize;
+            if let Some(count) = totals.levels.get_mut(level - 1) {
+                *count = count.saturating_sub(1);
+            }
+        }
+    }
+    if let Some(new) = new {
+        if old.map_or(true, |old| old.owner != new.owner) {
+            let balance = HOLDER_BALANCES
+                .may_load(storage, &new.owner)?
+                .unwrap_or_default();
+            if balance == 0 {
+                totals.holders += 1;
+            }
+            HOLDER_BALANCES.save(storage, &new.owner, &(balance + 1))?;
+        }
+        let traits = &new.extension;
+        totals.experience += Uint128::from(traits.experience);
+        totals.gold += Uint128::from(traits.gold);
+        totals.stamina += Uint128::from(traits.stamina);
+        if levels_counted {
+            count_level(&mut totals, level_curve, traits.experience);
+        }
+    }
+    COLLECTION_TOTALS.save(storage, &totals)
+}
+
+/// Adds an intern with the given experience to the level counts.
+pub fn count_level(totals: &mut CollectionTotals, level_curve: &LevelCurve, experience: u64) {
+    let level = level_curve.level(experience) as usize;
+    if totals.levels.len() < level {
+        totals.levels.resize(level, 0);
+    }
+    totals.levels[level - 1] += 1;
+}
+
+// payees splitting the contract's balance, which the owner withdraws if unset
+pub const PAYEES: Item<Vec<Payee>> = Item::new("payees");
+
+// amount of each denom paid out to each payee, by payee address and denom
+pub const RELEASED: Map<(&str, &str), Uint128> = Map::new("released");
+
+// amount of each denom paid out to all payees
+pub const TOTAL_RELEASED: Map<&str, Uint128> = Map::new("total_released");
+
+// part of the mint fees set aside for burn redemptions, in the mint fee denom
+pub const REDEMPTION_POOL: Item<Uint128> = Item::new("redemption_pool");
+
+/// Returns what each payee is owed of the contract's balance, in the same order. Everything the
+/// contract has received is split by shares, less the redemption pool and whatever each payee
+/// was already paid.
+pub fn pending_payments(
+    deps: Deps,
+    contract: &Addr,
+    payees: &[Payee],
+) -> StdResult<Vec<Vec<Coin>>> {
+    let config = CONFIG.load(deps.storage)?;
+    let pool = REDEMPTION_POOL.may_load(deps.storage)?.unwrap_or_default();
+    let total_shares: u64 = payees.iter().map(|payee| payee.shares).sum();
+    let mut pending = vec![vec![]; payees.len()];
+    for balance in deps.querier.query_all_balances(contract)? {
+        let released = TOTAL_RELEASED
+            .may_load(deps.storage, &balance.denom)?
+            .unwrap_or_default();
+        let reserved = match balance.denom == config.mint_fee.denom {
+            true => pool,
+            false => Uint128::zero(),
+        };
+        let received = balance.amount.saturating_sub(reserved) + released;
+        for (payee, owed) in payees.iter().zip(pending.iter_mut()) {
+            let paid = RELEASED
+                .may_load(deps.storage, (&payee.address, &balance.denom))?
+                .unwrap_or_default();
+            let amount = received
+                .multiply_ratio(payee.shares, total_shares)
+                .saturating_sub(paid);
+            if !amount.is_zero() {
+                owed.push(Coin {
+                    denom: balance.denom.clone(),
+                    amount,
+                });
+            }
+        }
+    }
+    Ok(pending)
+}
+
+// sha256 hash of the final metadata ordering, committed to before the reveal
+pub const PROVENANCE_HASH: Item<String> = Item::new("provenance_hash");
+
+// terrand round the reveal offset is drawn from, set when the owner requests the reveal
+pub const REVEAL_ROUND: Item<u64> = Item::new("reveal_round");
+
+// random offset applied to the metadata ordering, set once the collection is revealed
+pub const REVEAL_OFFSET: Item<u64> = Item::new("reveal_offset");
+
+/// Gathers the collection-wide state needed to display token metadata.
+pub fn metadata_context(storage: &dyn Storage) -> StdResult<MetadataContext> {
+    let config = CONFIG.load(storage)?;
+    let reveal_offset = REVEAL_OFFSET.may_load(storage)?;
+    let revealed = PROVENANCE_HASH.may_load(storage)?.is_none() || reveal_offset.is_some();
+    let placeholder = match revealed {
+        true => None,
+        false => Some(config.placeholder.unwrap_or_default()),
+    };
+    Ok(MetadataContext {
+        placeholder,
+        royalty: None,
+        base_uri: config.base_uri,
+        image_base_uri: config.image_base_uri,
+        reveal_offset,
+        token_supply: config.token_supply,
+        render_svg: config.render_svg,
+        level_curve: config.level_curve,
+    })
+}
+
+// per-token royalties that take precedence over the configured one
+pub const TOKEN_ROYALTIES: Map<&str, Royalty> = Map::new("token_royalties");
+
+/// Returns the royalty owed on secondary sales of the given token, if any.
+pub fn token_royalty(storage: &dyn Storage, token_id: &str) -> StdResult<Option<Royalty>> {
+    match TOKEN_ROYALTIES.may_load(storage, token_id)? {
+        Some(royalty) => Ok(Some(royalty)),
+        None => Ok(CONFIG.load(storage)?.royalty),
+    }
+}
@@ -6,9 +6,9 @@ use cosmwasm_std::{
 };
 use cw2::set_contract_version;
 use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
-use internnft::nft::ExecuteMsg::UpdateTrait;
-use internnft::nft::InternTokenInfo;
+use internnft::nft::ExecuteMsg::AdjustTraits;
 use internnft::nft::QueryMsg::InternNftInfo;
+use internnft::nft::{InternTokenInfo, TraitDeltas};
 use internnft::staking::ContractQuery::GetRandomness;
 use internnft::staking::{
     Config, Cw721HookMsg, ExecuteMsg, GetRandomResponse, InstantiateMsg, QueryMsg, StakingInfo,
@@ -221,7 +221,6 @@ pub fn withdraw_nft(
     }?;
 
     let mut new_staking_info: StakingInfo = staking_info.clone();
-    let mut new_token_info: InternTokenInfo = token_info.clone();
 
     //update gold or experience
     //1. calculate stamina lost
@@ -302,17 +301,18 @@ pub fn withdraw_nft(
 
     STAKING_INFO.save(deps.storage, token_id.clone(), &new_staking_info)?;
 
-    //updating the token information
-    new_token_info.extension.experience = token_info.extension.experience + added_exp;
-    new_token_info.extension.gold = token_info.extension.gold + added_gold;
-
+    //updating the token information, relative to whatever it holds by then
     let update_message = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: config.nft_contract_addr.to_string(),
-        msg: to_binary(&UpdateTrait {
+        msg: to_binary(&AdjustTraits {
             token_id: token_id.clone(),
-            exp: new_token_info.extension.experience,
-            gold: new_token_info.extension.gold,
-            stamina: token_info.extension.stamina,
+            deltas: TraitDeltas {
+                experience: added_exp as i64,
+                gold: added_gold as i64,
+                stamina: 0,
+            },
+            expected: None,
+            reason: Some(format!("unstake {}", staking_info.staking_type)),
         })?,
         funds: vec![],
     });
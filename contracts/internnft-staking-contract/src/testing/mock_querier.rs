@@ -63,6 +63,12 @@ impl WasmMockQuerier {
                         worker: "terra1x46rqay4d3cssq8gxxvqz8xt6nwlz4td20k38v".to_string(),
                     })))
                 }
+                // like the nft contract, only numeric token ids are accepted
+                QueryMsg::InternNftInfo { token_id } if token_id.parse::<u64>().is_err() => {
+                    SystemResult::Ok(ContractResult::Err(
+                        "expected numeric token identifier".to_string(),
+                    ))
+                }
                 QueryMsg::InternNftInfo { token_id } => {
                     SystemResult::Ok(ContractResult::from(to_binary(&InternTokenInfo {
                         owner: Addr::unchecked(String::from("addr000") + token_id.as_str()),
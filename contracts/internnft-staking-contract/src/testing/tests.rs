@@ -1,7 +1,8 @@
 use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{to_binary, Addr, CosmosMsg, Response, Timestamp, WasmMsg};
 use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
-use internnft::nft::ExecuteMsg::UpdateTrait;
+use internnft::nft::ExecuteMsg::AdjustTraits;
+use internnft::nft::TraitDeltas;
 use internnft::staking::{Config, Cw721HookMsg, InstantiateMsg, StakingInfo};
 
 use crate::contract::{instantiate, query_config, query_staking_info, stake, withdraw_nft};
@@ -367,11 +368,15 @@ fn test_stake_unstake_gold_stamina_not_depleted() {
     let msgs = vec![
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "internnft0000".to_string(),
-            msg: to_binary(&UpdateTrait {
+            msg: to_binary(&AdjustTraits {
                 token_id: "0".to_string(),
-                exp: 0,
-                gold: added_gold,
-                stamina: 100,
+                deltas: TraitDeltas {
+                    experience: 0,
+                    gold: added_gold as i64,
+                    stamina: 0,
+                },
+                expected: None,
+                reason: Some("unstake gold".to_string()),
             })
             .unwrap(),
             funds: vec![],
@@ -466,11 +471,15 @@ fn test_stake_unstake_exp_stamina_not_depleted() {
     let msgs = vec![
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "internnft0000".to_string(),
-            msg: to_binary(&UpdateTrait {
+            msg: to_binary(&AdjustTraits {
                 token_id: "0".to_string(),
-                exp: staked_blocks,
-                gold: 0,
-                stamina: 100,
+                deltas: TraitDeltas {
+                    experience: staked_blocks as i64,
+                    gold: 0,
+                    stamina: 0,
+                },
+                expected: None,
+                reason: Some("unstake exp".to_string()),
             })
             .unwrap(),
             funds: vec![],
@@ -662,11 +671,15 @@ fn test_stake_unstake_gold_stamina_depleted() {
     let msgs = vec![
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "internnft0000".to_string(),
-            msg: to_binary(&UpdateTrait {
+            msg: to_binary(&AdjustTraits {
                 token_id: "0".to_string(),
-                exp: 0,
-                gold: added_gold,
-                stamina: 100,
+                deltas: TraitDeltas {
+                    experience: 0,
+                    gold: added_gold as i64,
+                    stamina: 0,
+                },
+                expected: None,
+                reason: Some("unstake gold".to_string()),
             })
             .unwrap(),
             funds: vec![],
@@ -778,11 +791,15 @@ fn test_stake_unstake_exp_stamina_depleted() {
     let msgs = vec![
         CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: "internnft0000".to_string(),
-            msg: to_binary(&UpdateTrait {
+            msg: to_binary(&AdjustTraits {
                 token_id: "0".to_string(),
-                exp: added_exp,
-                gold: 0,
-                stamina: 100,
+                deltas: TraitDeltas {
+                    experience: added_exp as i64,
+                    gold: 0,
+                    stamina: 0,
+                },
+                expected: None,
+                reason: Some("unstake exp".to_string()),
             })
             .unwrap(),
             funds: vec![],
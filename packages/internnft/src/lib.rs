@@ -1,2 +1,3 @@
 pub mod nft;
 pub mod staking;
+pub mod svg;
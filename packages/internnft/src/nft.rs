@@ -1,11 +1,14 @@
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-use cosmwasm_std::{Addr, Binary, Coin, StdError, StdResult};
+use cosmwasm_std::{to_vec, Addr, Binary, Coin, Decimal, StdError, StdResult, Timestamp, Uint128};
+use cw20::Cw20ReceiveMsg;
 use cw721::{Expiration, OwnerOfResponse};
 use cw721_base::msg::{ExecuteMsg as CW721ExecuteMsg, QueryMsg as CW721QueryMsg};
 use cw721_base::state::Approval;
 
+use crate::svg::render_intern;
+
 // ----------------- begin CW721 ^0.9.2 shim ----------------- //
 
 // adapted from: https://github.com/CosmWasm/cw-nfts/blob/5e1e72a3682f988d4504b94f2e203dd4a5a99ad9/contracts/cw721-metadata-onchain/src/lib.rs#L7-L26
@@ -28,6 +31,11 @@ pub struct Cw721Metadata {
     pub background_color: Option<String>,
     pub animation_url: Option<String>,
     pub youtube_url: Option<String>,
+    // see: https://github.com/CosmWasm/cw-nfts/tree/main/contracts/cw2981-royalties
+    // whole percent as in CW2981, so fractional royalties are rounded down here; the
+    // RoyaltyInfo query returns the exact amount
+    pub royalty_percentage: Option<u64>,
+    pub royalty_payment_address: Option<String>,
 }
 
 // adapted from: https://github.com/CosmWasm/cw-nfts/blob/5e1e72a3682f988d4504b94f2e203dd4a5a99ad9/packages/cw721/src/query.rs#L93-L109
@@ -61,6 +69,243 @@ pub struct Config {
     pub mint_fee: Coin,
     //the staking contract that can make changes to gold and exp
     pub staking_contract: String,
+    /// PKCS#1 DER encoded RSA public key used to verify mint vouchers (disabled if unset)
+    pub voucher_public_key: Option<Binary>,
+    /// Root of the sha256 Merkle tree of allowlisted addresses (disabled if unset)
+    pub allowlist_root: Option<Binary>,
+    /// The maximum number of allowlist mints per allowlisted address
+    pub allowlist_limit: u32,
+    /// Ordered schedule of mint phases. When empty, minting is always open at `mint_fee`.
+    pub mint_phases: Vec<MintPhase>,
+    /// Part of `token_supply` set aside for owner airdrops, which public mints can't consume
+    pub reserved_supply: u64,
+    /// LoTerra terrand contract used to roll the starting traits of new interns
+    pub terrand_addr: Option<String>,
+    /// Rarity tiers new interns are rolled from, weighted by their `weight`. Interns start
+    /// without stamina until their traits are rolled, see `ExecuteMsg::RollTraits`.
+    /// Without tiers (or terrand), every intern starts with the same stamina.
+    pub rarity_tiers: Vec<RarityTier>,
+    /// Metadata shown in place of each intern's own until the collection is revealed
+    pub placeholder: Option<PlaceholderMetadata>,
+    /// Share of each mint fee set aside for burn redemptions, kept out of the payees' split and
+    /// withdrawals. Burning an intern pays out an even share of the set aside fees between all
+    /// held interns, and the mint fee denom is locked while anything is set aside (nothing is
+    /// set aside if unset)
+    pub burn_redemption: Option<Decimal>,
+    /// Royalty on secondary sales, unless overridden for a particular token
+    pub royalty: Option<Royalty>,
+    /// Base URI of the metadata JSON files, resolved to `{base_uri}/{token_id}`
+    pub base_uri: Option<String>,
+    /// Base URI of the images, resolved to `{image_base_uri}/{token_id}` unless a token has its own
+    pub image_base_uri: Option<String>,
+    /// Render each intern as an SVG from its traits, served through a data URI `token_uri`
+    /// instead of `base_uri`
+    pub render_svg: bool,
+    /// Experience thresholds of each level
+    pub level_curve: LevelCurve,
+    /// CW20 token gold can be withdrawn into (disabled if unset)
+    pub gold_token: Option<GoldToken>,
+    /// Apply `wallet_limit` to transfers and sends too, not only to mints
+    pub transfer_wallet_limit: bool,
+    /// Contracts such as marketplaces that can receive any number of interns when
+    /// `transfer_wallet_limit` is on, and return them to any wallet. The staking contract is
+    /// always exempt.
+    pub wallet_limit_exempt: Vec<String>,
+    /// Gold an intern spends each time its name or description is changed
+    pub profile_fee: u64,
+}
+
+/// Changes to the contract config. Fields left out stay as they are, and optional settings are
+/// cleared by setting them to null.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct ConfigUpdate {
+    pub token_supply: Option<u64>,
+    pub wallet_limit: Option<u32>,
+    pub mint_fee: Option<Coin>,
+    pub staking_contract: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voucher_public_key: Option<Option<Binary>>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub allowlist_root: Option<Option<Binary>>,
+    pub allowlist_limit: Option<u32>,
+    pub mint_phases: Option<Vec<MintPhase>>,
+    pub reserved_supply: Option<u64>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub terrand_addr: Option<Option<String>>,
+    pub rarity_tiers: Option<Vec<RarityTier>>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub placeholder: Option<Option<PlaceholderMetadata>>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub burn_redemption: Option<Option<Decimal>>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub royalty: Option<Option<Royalty>>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub base_uri: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub image_base_uri: Option<Option<String>>,
+    pub render_svg: Option<bool>,
+    pub level_curve: Option<LevelCurve>,
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gold_token: Option<Option<GoldToken>>,
+    pub transfer_wallet_limit: Option<bool>,
+    pub wallet_limit_exempt: Option<Vec<String>>,
+    pub profile_fee: Option<u64>,
+}
+
+// tells a field set to null (Some(None)) apart from one left out (None)
+fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// CW20 token that interns' gold converts into and back. The NFT contract has to be its minter.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct GoldToken {
+    pub address: String,
+    /// Amount of the token (in its smallest unit) a single gold converts into
+    pub rate: Uint128,
+}
+
+/// Hook messages accepted along with gold tokens sent to the NFT contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GoldHookMsg {
+    /// Convert the received tokens back into gold held by the given intern
+    Deposit { token_id: String },
+}
+
+/// Royalty owed to the collection on secondary sales of an intern.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Royalty {
+    pub payment_address: String,
+    /// Share of the sale price, in basis points (1/100th of a percent)
+    pub basis_points: u16,
+}
+
+impl Royalty {
+    pub fn amount(&self, sale_price: Uint128) -> Uint128 {
+        sale_price.multiply_ratio(self.basis_points, 10_000u128)
+    }
+}
+
+/// Metadata shown for every intern before the reveal. Unset fields fall back to the intern's
+/// own, except for the image which stays hidden.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PlaceholderMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+impl Config {
+    /// Returns the first mint phase that is active at the given block time.
+    pub fn active_mint_phase(&self, time: Timestamp) -> Option<&MintPhase> {
+        self.mint_phases.iter().find(|phase| phase.is_active(time))
+    }
+}
+
+/// A scheduled window of the mint with its own price and limits.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintPhase {
+    pub name: String,
+    /// Block time (in seconds) at which the phase starts
+    pub start_time: u64,
+    /// Block time (in seconds) at which the phase ends, open-ended if unset
+    pub end_time: Option<u64>,
+    /// The price to mint a new intern during the phase (doesn't apply to the contract owner)
+    pub price: Coin,
+    /// The maximum number of tokens a particular wallet can mint during the phase
+    pub wallet_limit: u32,
+    /// The maximum number of tokens minted during the phase, bounded only by `token_supply` if unset
+    pub supply: Option<u64>,
+    /// Whether minting during the phase requires an allowlist proof
+    pub allowlist: bool,
+}
+
+impl MintPhase {
+    pub fn is_active(&self, time: Timestamp) -> bool {
+        let seconds = time.seconds();
+        let ended = match self.end_time {
+            Some(end_time) => seconds >= end_time,
+            None => false,
+        };
+        seconds >= self.start_time && !ended
+    }
+}
+
+/// A rarity tier new interns can roll, along with the starting stamina range for the tier.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RarityTier {
+    pub name: String,
+    /// Relative chance of rolling the tier
+    pub weight: u32,
+    pub min_stamina: u64,
+    pub max_stamina: u64,
+}
+
+/// The starting trait roll a newly minted intern is waiting on, with the tiers in force at mint.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingRoll {
+    /// Terrand round the traits are rolled from
+    pub round: u64,
+    pub tiers: Vec<RarityTier>,
+}
+
+/// An allowance to mint interns, signed off-chain by the allowlist service.
+/// The signature covers the sha256 digest of the voucher's JSON encoding.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MintVoucher {
+    /// The address of the contract the voucher can be redeemed at
+    pub contract: String,
+    /// The address receiving the minted interns
+    pub recipient: String,
+    /// The number of interns that can be minted with this voucher
+    pub quantity: u32,
+    /// The price paid for each intern
+    pub price: Coin,
+    /// Block time (in seconds) after which the voucher can no longer be redeemed
+    pub expires: u64,
+    /// Unique value preventing a voucher from being redeemed twice
+    pub nonce: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
@@ -70,9 +315,146 @@ pub struct InternExtension {
     pub stamina: u64,
 }
 
+/// The traits of an intern that game contracts can be allowed to update.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum InternTrait {
+    Experience,
+    Gold,
+    Stamina,
+}
+
+impl InternTrait {
+    pub const ALL: [InternTrait; 3] = [
+        InternTrait::Experience,
+        InternTrait::Gold,
+        InternTrait::Stamina,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InternTrait::Experience => "experience",
+            InternTrait::Gold => "gold",
+            InternTrait::Stamina => "stamina",
+        }
+    }
+}
+
+/// Direction in which paginated queries are sorted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Signed changes to apply to an intern's traits.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy, Default)]
+pub struct TraitDeltas {
+    pub experience: i64,
+    pub gold: i64,
+    pub stamina: i64,
+}
+
+impl TraitDeltas {
+    pub fn get(&self, intern_trait: InternTrait) -> i64 {
+        match intern_trait {
+            InternTrait::Experience => self.experience,
+            InternTrait::Gold => self.gold,
+            InternTrait::Stamina => self.stamina,
+        }
+    }
+}
+
+/// A change made to an intern's traits, by a game contract or by moving gold in or out of it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TraitChange {
+    /// Sequence number of the change among the intern's changes, starting from 1
+    pub id: u64,
+    /// The game contract that made the change, or the address that moved the gold
+    pub updater: String,
+    pub block_height: u64,
+    pub old: InternExtension,
+    pub new: InternExtension,
+    pub reason: Option<String>,
+}
+
+/// A game contract allowed to update the given traits of any intern.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TraitUpdater {
+    pub address: String,
+    pub traits: Vec<InternTrait>,
+}
+
+/// Levels reached at increasing experience thresholds. Every intern starts at level 1.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LevelCurve {
+    /// Experience needed to reach level 2, 3 and so on, in increasing order
+    pub thresholds: Vec<u64>,
+}
+
+impl LevelCurve {
+    pub fn level(&self, experience: u64) -> u32 {
+        1 + self
+            .thresholds
+            .iter()
+            .take_while(|threshold| experience >= **threshold)
+            .count() as u32
+    }
+
+    /// Returns the experience needed for the next level (none at the top level) and how far
+    /// along the way to it the intern is.
+    pub fn progress(&self, experience: u64) -> (Option<u64>, Decimal) {
+        let level = self.level(experience) as usize;
+        let current = match level {
+            1 => 0,
+            _ => self.thresholds[level - 2],
+        };
+        match self.thresholds.get(level - 1) {
+            Some(next) => (
+                Some(*next),
+                Decimal::from_ratio(experience - current, next - current),
+            ),
+            None => (None, Decimal::one()),
+        }
+    }
+
+    pub fn validate(&self) -> StdResult<()> {
+        let increasing = self.thresholds.windows(2).all(|pair| pair[0] < pair[1]);
+        if !increasing || self.thresholds.first() == Some(&0) {
+            return Err(StdError::generic_err(
+                "level thresholds must be positive and increasing",
+            ));
+        }
+        Ok(())
+    }
+}
+
 impl InternExtension {
-    pub fn as_traits(&self) -> Vec<Cw721Trait> {
+    pub fn get(&self, intern_trait: InternTrait) -> u64 {
+        match intern_trait {
+            InternTrait::Experience => self.experience,
+            InternTrait::Gold => self.gold,
+            InternTrait::Stamina => self.stamina,
+        }
+    }
+
+    /// Lists the traits that differ between the two extensions.
+    pub fn changed_traits(&self, other: &InternExtension) -> Vec<InternTrait> {
+        InternTrait::ALL
+            .iter()
+            .filter(|intern_trait| self.get(**intern_trait) != other.get(**intern_trait))
+            .copied()
+            .collect()
+    }
+
+    pub fn as_traits(&self, level: u32) -> Vec<Cw721Trait> {
         vec![
+            Cw721Trait {
+                display_type: None,
+                trait_type: "level".to_string(),
+                value: level.to_string(),
+            },
             Cw721Trait {
                 display_type: None,
                 trait_type: "experience".to_string(),
@@ -102,22 +484,112 @@ pub struct InternTokenInfo {
     pub extension: InternExtension,
 }
 
+/// Contract state beyond the token itself that shapes how its metadata is displayed.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct MetadataContext {
+    /// Set while the collection is not yet revealed
+    pub placeholder: Option<PlaceholderMetadata>,
+    /// Royalty of the displayed token, if any
+    pub royalty: Option<Royalty>,
+    pub base_uri: Option<String>,
+    pub image_base_uri: Option<String>,
+    /// Offset drawn at reveal, which shifts the token ids used in metadata URIs
+    pub reveal_offset: Option<u64>,
+    pub token_supply: u64,
+    pub render_svg: bool,
+    pub level_curve: LevelCurve,
+}
+
+impl MetadataContext {
+    /// Returns the token as it should be displayed to holders and marketplaces.
+    pub fn display(&self, token_id: &str, token: &InternTokenInfo) -> InternTokenInfo {
+        let mut token = token.clone();
+        match &self.placeholder {
+            Some(placeholder) => {
+                token.name = placeholder.name.clone().unwrap_or(token.name);
+                token.description = placeholder.description.clone().unwrap_or(token.description);
+                token.image = placeholder.image.clone();
+            }
+            None if token.image.is_none() => {
+                token.image = self.resolve_uri(&self.image_base_uri, token_id);
+            }
+            None => {}
+        }
+        token
+    }
+
+    /// Returns the URI of the token's metadata JSON, hidden until the collection is revealed.
+    /// On-chain rendered interns embed their `metadata` in the URI itself.
+    pub fn token_uri(&self, token_id: &str, metadata: &Cw721Metadata) -> Option<String> {
+        match (&self.placeholder, self.render_svg) {
+            (Some(_), _) => None,
+            (None, true) => Some(format!(
+                "data:application/json;base64,{}",
+                base64::encode(to_vec(metadata).ok()?)
+            )),
+            (None, false) => self.resolve_uri(&self.base_uri, token_id),
+        }
+    }
+
+    /// Returns the SVG rendering of the token, hidden until the collection is revealed.
+    pub fn image_data(&self, token: &InternTokenInfo) -> Option<String> {
+        match (&self.placeholder, self.render_svg) {
+            (None, true) => Some(render_intern(
+                &token.name,
+                &token.extension,
+                self.level_curve.level(token.extension.experience),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Resolves `{base}/{numeric_id}`, where the id is shifted by the reveal offset (if any).
+    fn resolve_uri(&self, base: &Option<String>, token_id: &str) -> Option<String> {
+        let base = base.as_ref()?;
+        let mut id = numeric_token_id(token_id.to_string())
+            .ok()?
+            .parse::<u64>()
+            .ok()?;
+        if let Some(offset) = self.reveal_offset {
+            id = (id - 1 + offset) % self.token_supply.max(1) + 1;
+        }
+        Some(format!("{}/{}", base.trim_end_matches('/'), id))
+    }
+}
+
 impl InternTokenInfo {
-    pub fn as_cw721_nft_info(&self) -> Cw721NftInfoResponse {
+    pub fn as_cw721_nft_info(
+        &self,
+        token_id: &str,
+        context: &MetadataContext,
+    ) -> Cw721NftInfoResponse {
+        let token = context.display(token_id, self);
+        let extension = Cw721Metadata {
+            image_data: context.image_data(&token),
+            name: Some(token.name),
+            image: token.image,
+            description: Some(token.description),
+            attributes: Some(
+                token
+                    .extension
+                    .as_traits(context.level_curve.level(token.extension.experience)),
+            ),
+            external_url: None,
+            animation_url: None,
+            background_color: None,
+            youtube_url: None,
+            royalty_percentage: context
+                .royalty
+                .as_ref()
+                .map(|royalty| royalty.basis_points as u64 / 100),
+            royalty_payment_address: context
+                .royalty
+                .as_ref()
+                .map(|royalty| royalty.payment_address.clone()),
+        };
         Cw721NftInfoResponse {
-            token_uri: None,
-            extension: Cw721Metadata {
-                name: Some(self.name.clone()),
-                // TODO: Put something for image.
-                image: None,
-                description: Some(self.description.clone()),
-                attributes: Some(self.extension.as_traits()),
-                image_data: None,
-                external_url: None,
-                animation_url: None,
-                background_color: None,
-                youtube_url: None,
-            },
+            token_uri: context.token_uri(token_id, &extension),
+            extension,
         }
     }
 }
@@ -144,27 +616,153 @@ pub fn numeric_token_id(full_token_id: String) -> StdResult<String> {
 #[serde(rename_all = "snake_case")]
 pub struct InstantiateMsg {
     pub config: Config,
+    /// sha256 hash (hex encoded) of the final metadata ordering, committed to ahead of
+    /// a delayed reveal, which freezes `token_supply`. The collection is revealed from the
+    /// start if unset.
+    pub provenance_hash: Option<String>,
 }
 
 /// This overrides the ExecuteMsg enum defined in cw721-base
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
+// messages are deserialized once per call, so boxing the config isn't worth it
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
-    Mint {},
-    /// Update token minting and supply configuration.
+    /// Mint `quantity` new interns (one if unset). Allowlisted addresses can provide a Merkle
+    /// proof of their inclusion in the allowlist to mint against their allowlist allotment.
+    Mint {
+        quantity: Option<u32>,
+        proof: Option<Vec<Binary>>,
+    },
+    /// Mint interns using a voucher signed by the allowlist service.
+    MintWithVoucher {
+        voucher: MintVoucher,
+        signature: Binary,
+    },
+    /// Update token minting and supply configuration, changing only the given fields.
+    /// Only the contract owner can update the config.
     UpdateConfig {
-        config: Config,
+        config: ConfigUpdate,
+    },
+    /// Mint interns for free to each recipient, drawing from the reserved supply first.
+    /// Only the contract owner can airdrop.
+    Airdrop {
+        recipients: Vec<(String, u32)>,
+    },
+    /// Roll the starting traits of newly minted interns from the terrand round they committed
+    /// to at mint time, once that round is published. Anyone can roll.
+    RollTraits {
+        token_ids: Vec<String>,
+    },
+    /// Request the reveal of the collection, committing to a terrand round that isn't published
+    /// yet. Only the contract owner can reveal, and only once.
+    Reveal {},
+    /// Reveal the collection once the requested round is published, shifting the committed
+    /// metadata ordering by an offset drawn from its randomness. Anyone can finalize.
+    FinalizeReveal {},
+    /// Override the royalty of a single intern, or clear its override if `royalty` is unset.
+    /// Only the contract owner can update royalties.
+    UpdateTokenRoyalty {
+        token_id: String,
+        royalty: Option<Royalty>,
+    },
+    /// Set the image of a single intern, overriding `image_base_uri`, or clear it if `image`
+    /// is unset. Only the contract owner can update images.
+    UpdateTokenImage {
+        token_id: String,
+        image: Option<String>,
+    },
+    /// Withdraw gold from an intern, minting the equivalent amount of the gold token.
+    /// Only the owner of the intern can withdraw its gold.
+    WithdrawGold {
+        token_id: String,
+        amount: u64,
+    },
+    /// Deposit gold tokens back into an intern, see GoldHookMsg.
+    Receive(Cw20ReceiveMsg),
+    /// Burn an intern, paying out its redemption if one is configured.
+    /// The owner of the intern or an approved spender or operator can burn it.
+    Burn {
+        token_id: String,
+    },
+    /// Rename an intern and/or change its description, spending `profile_fee` of its gold. At
+    /// least one of the two has to be set, and nothing can change while trait updates are paused.
+    /// Names are unique regardless of case. Only the owner of the intern can set its profile.
+    SetProfile {
+        token_id: String,
+        name: Option<String>,
+        description: Option<String>,
     },
     /// Withdraw from current contract balance to owner address.
     Withdraw {
         amount: Vec<Coin>,
     },
+    /// Propose a new contract owner, who has to accept before `expires` (a week from now if
+    /// unset). Replaces any pending proposal. Only the contract owner can propose.
+    ProposeNewOwner {
+        new_owner: String,
+        expires: Option<Expiration>,
+    },
+    /// Become the contract owner. Only the proposed owner can accept, before the proposal
+    /// expires.
+    AcceptOwnership {},
+    /// Count the levels of the next `limit` interns after a level curve change. Collection stats
+    /// only cover the interns counted so far until every intern has been counted.
+    /// Only the contract owner can recount.
+    RecountLevels {
+        limit: Option<u32>,
+    },
+    /// Pause or resume minting, transfers and trait updates independently, leaving the ones
+    /// that aren't set as they are. Only the contract owner can pause.
+    SetPaused {
+        minting: Option<bool>,
+        transfers: Option<bool>,
+        trait_updates: Option<bool>,
+    },
+    /// Give up ownership of the contract for good, leaving everything that requires the owner
+    /// (config updates, withdrawals, airdrops, ...) disabled. Only the contract owner can renounce.
+    RenounceOwnership {},
+    /// Set the traits of an intern. Only the staking contract and registered updaters can
+    /// update traits, and updaters only the ones they were granted.
+    /// token_id is the numeric id of the intern, like "123".
     UpdateTrait {
         token_id: String,
         exp: u64,
         gold: u64,
         stamina: u64,
+        /// Recorded in the intern's trait history
+        reason: Option<String>,
+    },
+    /// Adjust the traits of an intern by the given deltas, so that concurrent updates from
+    /// different game contracts add up instead of overwriting each other. If `expected` is
+    /// set, the adjustment fails unless the intern's traits still match it.
+    /// Same permissions as UpdateTrait apply.
+    AdjustTraits {
+        token_id: String,
+        deltas: TraitDeltas,
+        expected: Option<InternExtension>,
+        /// Recorded in the intern's trait history
+        reason: Option<String>,
+    },
+    /// Allow a game contract to update the given traits, replacing any previous grant.
+    /// Only the contract owner can manage updaters.
+    AddUpdater {
+        address: String,
+        traits: Vec<InternTrait>,
+    },
+    /// Revoke a game contract's permission to update traits.
+    /// Only the contract owner can manage updaters.
+    RemoveUpdater {
+        address: String,
     },
+    /// Split the contract's balance between the given payees by their shares, settling
+    /// whatever the current payees are owed first. An empty list leaves withdrawals to the
+    /// contract owner. Only the contract owner can update payees.
+    UpdatePayees {
+        payees: Vec<Payee>,
+    },
+    /// Pay every payee what they're owed of the contract's balance. Anyone can distribute.
+    Distribute {},
     /// BELOW ARE COPIED FROM CW721-BASE
     TransferNft {
         recipient: String,
@@ -240,21 +838,28 @@ pub enum QueryMsg {
     /// Returns the current contract config
     /// Return type: Config
     Config {},
-    /// Returns all tokens owned by the given address, [] if unset.
+    /// Returns the contract owner and any pending ownership proposal
+    /// Return type: OwnerResponse
+    Owner {},
+    /// Returns which operations are currently paused
+    /// Return type: PauseState
+    PauseState {},
+    /// Returns all tokens owned by the given address, [] if unset. Pages by numeric token id.
     /// Return type: InternTokensResponse.
     InternTokens {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
     },
-    /// Lists all token_ids controlled by the contract.
+    /// Lists all tokens controlled by the contract. Pages by numeric token id.
     /// Return type: InternTokensResponse.
     AllInternTokens {
         start_after: Option<String>,
         limit: Option<u32>,
     },
     /// Returns metadata about one particular token, based on *ERC721 Metadata JSON Schema*
-    /// but directly from the contract: InternTokenInfo.
+    /// but directly from the contract, along with its level. Takes a numeric token id.
+    /// Return type: InternNftInfoResponse
     InternNftInfo {
         token_id: String,
     },
@@ -263,6 +868,68 @@ pub enum QueryMsg {
     NumTokensForOwner {
         owner: String,
     },
+    /// Returns the number of allowlist mints used and left for the given address,
+    /// assuming it is on the allowlist.
+    /// Return type: AllowlistMintsResponse
+    AllowlistMints {
+        address: String,
+    },
+    /// Returns the committed provenance hash and the reveal state.
+    /// Return type: ProvenanceResponse
+    Provenance {},
+    /// Returns the royalty owed to the collection when the given intern sells for
+    /// `sale_price`, following CW2981.
+    /// Return type: RoyaltiesInfoResponse
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+    /// Returns whether the collection pays royalties, following CW2981.
+    /// Return type: CheckRoyaltiesResponse
+    CheckRoyalties {},
+    /// Lists the ids of all burned interns.
+    /// Return type: BurnedTokensResponse
+    BurnedTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the active mint phase, its price and remaining supply, and optionally
+    /// how many more interns the given address can mint.
+    /// Return type: MintStatusResponse
+    MintStatus {
+        address: Option<String>,
+    },
+    /// Lists the changes made to the traits of the given intern, oldest first.
+    /// Return type: TraitHistoryResponse
+    TraitHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Ranks interns by experience or gold, highest first unless the order says otherwise.
+    /// Interns with equal values are ordered by numeric token id, in the same direction.
+    /// start_after is the numeric id of the last intern of the previous page.
+    /// Return type: LeaderboardResponse
+    Leaderboard {
+        #[serde(rename = "trait")]
+        intern_trait: InternTrait,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<SortOrder>,
+    },
+    /// Returns supply, holder, trait and level statistics for the whole collection
+    /// Return type: CollectionStatsResponse
+    CollectionStats {},
+    /// Lists the payees along with what they've been paid and are still owed
+    /// Return type: PayeesResponse
+    Payees {},
+    /// Lists the game contracts allowed to update traits, besides the staking contract
+    /// which can update all of them.
+    /// Return type: UpdatersResponse
+    Updaters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     // BELOW ARE COPIED FROM CW721-BASE
     OwnerOf {
@@ -356,9 +1023,195 @@ impl From<QueryMsg> for CW721QueryMsg {
     }
 }
 
+/// Superset of InternTokenInfo, which it can still be parsed as.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InternNftInfoResponse {
+    /// Same as the cw721 `NftInfo` token uri
+    pub token_uri: Option<String>,
+    pub owner: Addr,
+    pub approvals: Vec<Approval>,
+    pub name: String,
+    pub description: String,
+    pub image: Option<String>,
+    pub extension: InternExtension,
+    /// Rarity tier the intern rolled, if any
+    pub rarity: Option<String>,
+    pub level: u32,
+    /// Experience needed to reach the next level, unless the intern is at the top level
+    pub next_level_experience: Option<u64>,
+    /// Progress towards the next level, from 0 to 1
+    pub level_progress: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct InternTokenEntry {
+    /// numeric token id, like "123"
+    pub token_id: String,
+    pub info: InternTokenInfo,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct InternTokensResponse {
-    pub tokens: Vec<InternTokenInfo>,
+    pub tokens: Vec<InternTokenEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AllowlistMintsResponse {
+    pub minted: u32,
+    pub remaining: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MintStatusResponse {
+    /// Whether minting is currently open: not paused, and within a phase if a schedule is
+    /// configured
+    pub open: bool,
+    /// The active mint phase, if a schedule is configured
+    pub phase: Option<MintPhase>,
+    /// The price to mint a new intern
+    pub price: Coin,
+    /// The number of interns that can still be minted
+    pub remaining_supply: u64,
+    /// The number of interns the queried address can still mint
+    pub remaining_allowance: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CheckRoyaltiesResponse {
+    pub royalty_payments: bool,
+}
+
+/// A recipient of the contract's revenue, paid `shares` out of the total shares of all payees.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Payee {
+    pub address: String,
+    pub shares: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayeeInfo {
+    pub address: String,
+    pub shares: u64,
+    /// Amounts paid out so far, per denom
+    pub released: Vec<Coin>,
+    /// Amounts owed from the current balance, per denom
+    pub pending: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PayeesResponse {
+    pub payees: Vec<PayeeInfo>,
+}
+
+/// Running totals over all held interns, updated as interns are minted, transferred, burned
+/// and changed so that collection stats never iterate over the tokens.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct CollectionTotals {
+    /// Number of addresses holding at least one intern
+    pub holders: u64,
+    pub experience: Uint128,
+    pub gold: Uint128,
+    pub stamina: Uint128,
+    /// Number of interns at each level, starting from level 1
+    pub levels: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LevelCount {
+    pub level: u32,
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct CollectionStatsResponse {
+    /// Interns ever minted, including burned ones
+    pub minted: u64,
+    /// Interns that can still be minted, including the reserved supply
+    pub remaining_supply: u64,
+    /// Addresses holding at least one intern, leaving out the staking contract and
+    /// `wallet_limit_exempt` contracts
+    pub holders: u64,
+    pub total_experience: Uint128,
+    pub total_gold: Uint128,
+    pub total_stamina: Uint128,
+    /// Averages over the interns currently held
+    pub average_experience: Decimal,
+    pub average_gold: Decimal,
+    pub average_stamina: Decimal,
+    /// Number of held interns at each level that has any
+    pub levels: Vec<LevelCount>,
+    /// Whether levels are being recounted after a level curve change, in which case `levels`
+    /// only covers the interns counted so far
+    pub recounting_levels: bool,
+}
+
+/// Operations the contract owner has paused, e.g. during an incident.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PauseState {
+    /// Blocks all minting, including airdrops
+    pub minting: bool,
+    /// Blocks transferring and sending interns
+    pub transfers: bool,
+    /// Blocks trait updates from game contracts, and converting gold to and from the gold token
+    pub trait_updates: bool,
+}
+
+/// A proposed transfer of contract ownership, waiting to be accepted.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingOwner {
+    pub address: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OwnerResponse {
+    /// None once ownership has been renounced
+    pub owner: Option<String>,
+    pub pending_owner: Option<PendingOwner>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitHistoryResponse {
+    pub changes: Vec<TraitChange>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LeaderboardEntry {
+    /// numeric token id, like "123"
+    pub token_id: String,
+    pub token: InternTokenInfo,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LeaderboardResponse {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UpdatersResponse {
+    pub updaters: Vec<TraitUpdater>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BurnedTokensResponse {
+    pub tokens: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ProvenanceResponse {
+    /// sha256 hash of the final metadata ordering, committed to at instantiation
+    pub provenance_hash: Option<String>,
+    pub revealed: bool,
+    /// Terrand round the reveal offset is drawn from, once the reveal is requested
+    pub reveal_round: Option<u64>,
+    /// Random offset applied to the committed ordering at reveal
+    pub offset: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -385,7 +1238,7 @@ mod tests {
         };
 
         assert_eq!(
-            info.as_cw721_nft_info(),
+            info.as_cw721_nft_info("intern #1", &MetadataContext::default()),
             Cw721NftInfoResponse {
                 token_uri: None,
                 extension: Cw721Metadata {
@@ -393,6 +1246,11 @@ mod tests {
                     description: Some("testing description".to_string()),
                     image: None,
                     attributes: Some(vec![
+                        Cw721Trait {
+                            display_type: None,
+                            trait_type: "level".to_string(),
+                            value: "1".to_string(),
+                        },
                         Cw721Trait {
                             display_type: None,
                             trait_type: "experience".to_string(),
@@ -413,9 +1271,113 @@ mod tests {
                     animation_url: None,
                     youtube_url: None,
                     external_url: None,
-                    background_color: None
+                    background_color: None,
+                    royalty_percentage: None,
+                    royalty_payment_address: None,
                 }
             }
         )
     }
+
+    #[test]
+    fn placeholder_display() {
+        let info = InternTokenInfo {
+            name: "intern #1".to_string(),
+            owner: Addr::unchecked("testing owner"),
+            description: "testing description".to_string(),
+            image: Some("ipfs://intern.png".to_string()),
+            approvals: vec![],
+            extension: InternExtension {
+                experience: 10,
+                gold: 100,
+                stamina: 100,
+            },
+        };
+        let context = MetadataContext {
+            placeholder: Some(PlaceholderMetadata {
+                name: None,
+                description: Some("unrevealed".to_string()),
+                image: None,
+            }),
+            ..MetadataContext::default()
+        };
+
+        let token = context.display("intern #1", &info);
+        assert_eq!(token.name, "intern #1");
+        assert_eq!(token.description, "unrevealed");
+        assert_eq!(token.image, None);
+        assert_eq!(token.extension, info.extension);
+
+        assert_eq!(MetadataContext::default().display("intern #1", &info), info);
+    }
+
+    #[test]
+    fn level_curve() {
+        let curve = LevelCurve {
+            thresholds: vec![100, 300],
+        };
+        assert!(curve.validate().is_ok());
+        assert_eq!(curve.level(0), 1);
+        assert_eq!(curve.level(99), 1);
+        assert_eq!(curve.level(100), 2);
+        assert_eq!(curve.level(1000), 3);
+
+        assert_eq!(curve.progress(50), (Some(100), Decimal::percent(50)));
+        assert_eq!(curve.progress(150), (Some(300), Decimal::percent(25)));
+        assert_eq!(curve.progress(300), (None, Decimal::one()));
+        assert_eq!(LevelCurve::default().progress(10), (None, Decimal::one()));
+
+        let invalid = LevelCurve {
+            thresholds: vec![300, 100],
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn resolve_uris() {
+        let mut info = InternTokenInfo {
+            name: "intern #3".to_string(),
+            owner: Addr::unchecked("testing owner"),
+            description: "testing description".to_string(),
+            image: None,
+            approvals: vec![],
+            extension: InternExtension {
+                experience: 10,
+                gold: 100,
+                stamina: 100,
+            },
+        };
+        let mut context = MetadataContext {
+            base_uri: Some("ipfs://metadata/".to_string()),
+            image_base_uri: Some("ipfs://images".to_string()),
+            token_supply: 10,
+            ..MetadataContext::default()
+        };
+
+        let nft_info = info.as_cw721_nft_info("intern #3", &context);
+        assert_eq!(nft_info.token_uri, Some("ipfs://metadata/3".to_string()));
+        assert_eq!(
+            nft_info.extension.image,
+            Some("ipfs://images/3".to_string())
+        );
+
+        // the reveal offset shifts the ids, wrapping around the token supply
+        context.reveal_offset = Some(8);
+        let nft_info = info.as_cw721_nft_info("intern #3", &context);
+        assert_eq!(nft_info.token_uri, Some("ipfs://metadata/1".to_string()));
+
+        // renamed interns keep their ids
+        info.name = "Bob".to_string();
+        let nft_info = info.as_cw721_nft_info("intern #3", &context);
+        assert_eq!(nft_info.token_uri, Some("ipfs://metadata/1".to_string()));
+        assert_eq!(nft_info.extension.name, Some("Bob".to_string()));
+
+        // a token's own image takes precedence
+        info.image = Some("ipfs://custom.png".to_string());
+        let nft_info = info.as_cw721_nft_info("intern #3", &context);
+        assert_eq!(
+            nft_info.extension.image,
+            Some("ipfs://custom.png".to_string())
+        );
+    }
 }
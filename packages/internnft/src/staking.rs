@@ -39,6 +39,7 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Allows this contract to be on the receiving end of a SendNft{contract, token_id, msg} call
     /// to the nft contract. The same thing as sending CW721 tokens to a contract.
+    #[serde(rename = "receive_nft")]
     Receive(Cw721ReceiveMsg),
     UpdateConfig {
         nft_contract_addr: Addr,
@@ -0,0 +1,83 @@
+use crate::nft::InternExtension;
+
+const BAR_WIDTH: u64 = 200;
+
+/// Renders an intern card from its current traits, so the art changes as the intern does.
+pub fn render_intern(name: &str, extension: &InternExtension, level: u32) -> String {
+    let stats = [
+        ("experience", extension.experience, "#4caf50"),
+        ("gold", extension.gold, "#ffc107"),
+        ("stamina", extension.stamina, "#03a9f4"),
+    ];
+
+    let mut svg = String::from(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 350 350\">\
+         <style>text{font-family:monospace;fill:#fff}.title{font-size:24px}.stat{font-size:14px}</style>\
+         <rect width=\"350\" height=\"350\" fill=\"#1b1b2f\"/>",
+    );
+    svg.push_str(&format!(
+        "<text x=\"20\" y=\"50\" class=\"title\">{}</text>\
+         <text x=\"330\" y=\"50\" class=\"stat\" text-anchor=\"end\">level {}</text>",
+        escape(name),
+        level
+    ));
+    for (i, (label, value, color)) in stats.iter().enumerate() {
+        let y = 110 + i as u64 * 70;
+        svg.push_str(&format!(
+            "<text x=\"20\" y=\"{}\" class=\"stat\">{} {}</text>\
+             <rect x=\"20\" y=\"{}\" width=\"{}\" height=\"12\" fill=\"#33334d\"/>\
+             <rect x=\"20\" y=\"{}\" width=\"{}\" height=\"12\" fill=\"{}\"/>",
+            y,
+            label,
+            value,
+            y + 12,
+            BAR_WIDTH,
+            y + 12,
+            bar_length(*value),
+            color
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+// traits are unbounded, so bars fill up asymptotically: half full at 100, nearly full by 1000
+fn bar_length(value: u64) -> u64 {
+    let value = value as u128;
+    (BAR_WIDTH as u128 * value / (value + 100)) as u64
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars_follow_traits() {
+        assert_eq!(bar_length(0), 0);
+        assert_eq!(bar_length(100), BAR_WIDTH / 2);
+        assert!(bar_length(u64::MAX) <= BAR_WIDTH);
+
+        let mut extension = InternExtension {
+            experience: 0,
+            gold: 100,
+            stamina: 100,
+        };
+        let svg = render_intern("<intern #1>", &extension, 1);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("&lt;intern #1&gt;"));
+        assert!(svg.contains("experience 0"));
+        assert!(svg.contains("level 1"));
+
+        extension.experience = 300;
+        let svg = render_intern("intern #1", &extension, 3);
+        assert!(svg.contains("experience 300"));
+        assert!(svg.contains("level 3"));
+    }
+}